@@ -1 +1,73 @@
 pub mod helper_macros;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use aoc_plumbing::DynProblem;
+use criterion::Criterion;
+
+/// Finds `input.txt` in the `day-NNN-*` directory for the given day, relative
+/// to the root of the workspace.
+pub fn input_path(year: usize, day: usize) -> Option<PathBuf> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+
+    aoc_solutions::input_path(&root, year, day)
+}
+
+/// Benchmarks parsing and each part individually, as well as the combined
+/// solution. Parts are independent and repeatable, so each part is solved
+/// over and over on the same instance. The combined solution solves both parts
+/// at once for days registered as parallel, as the cli does.
+pub fn bench_day(c: &mut Criterion, problem: &dyn DynProblem) {
+    let Some(path) = input_path(problem.year(), problem.day()) else {
+        return;
+    };
+
+    let mut group = c.benchmark_group(format!("{} {}", problem.year(), problem.problem_label()));
+    let input = fs::read_to_string(path).expect("Could not load input");
+
+    group.bench_function("Parse", |b| {
+        b.iter(|| problem.instance(&input).expect("Could not parse input"))
+    });
+
+    let inst = problem.instance(&input).expect("Could not parse input");
+    group.bench_function("Part 1", |b| {
+        b.iter(|| inst.part_one().expect("Failed to solve part one"))
+    });
+    group.bench_function("Part 2", |b| {
+        b.iter(|| inst.part_two().expect("Failed to solve part two"))
+    });
+
+    group.bench_function("Combined (including parsing)", |b| {
+        b.iter(|| problem.solve_parallel(&input).expect("Failed to solve"))
+    });
+    group.finish();
+}
+
+/// Benchmarks solving every given problem of `year`, one after the other.
+pub fn bench_combined<'a>(
+    c: &mut Criterion,
+    year: usize,
+    problems: impl IntoIterator<Item = &'a dyn DynProblem>,
+    measurement_time: Duration,
+) {
+    let problems: Vec<_> = problems
+        .into_iter()
+        .filter_map(|p| input_path(p.year(), p.day()).map(|path| (p, path)))
+        .collect();
+
+    let mut group = c.benchmark_group(format!("Advent of Code {}", year));
+    group.measurement_time(measurement_time);
+    group.bench_function("Total runtime for all solutions, including parsing", |b| {
+        b.iter(|| {
+            for (problem, path) in &problems {
+                let input = fs::read_to_string(path).expect("Failed to open file");
+                problem.solve_parallel(&input).expect("Failed to solve");
+            }
+        })
+    });
+    group.finish();
+}
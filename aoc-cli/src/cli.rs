@@ -1,188 +1,1399 @@
 use std::{
-    marker::PhantomData,
+    collections::BTreeMap,
+    ffi::OsStr,
+    fmt::Display,
+    fs::File,
+    io::{BufRead, BufReader, Read},
     path::{Path, PathBuf},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::Duration,
 };
 
-use anyhow::{Context, Result};
-use aoc_plumbing::Problem;
-use cafeteria::Cafeteria;
-use clap::{Args, CommandFactory, Parser, Subcommand};
-use clap_complete::{generate, shells::Zsh};
-use factory::Factory;
-use gift_shop::GiftShop;
-use laboratories::Laboratories;
-use lobby::Lobby;
-use playground::Playground;
-use printing_department::PrintingDepartment;
-use reactor::Reactor;
-use secret_entrance::SecretEntrance;
-use trash_compactor::TrashCompactor;
-// import_marker
-
-// I'm not proud
-macro_rules! generate_cli {
-    ($(($name:ident, $day:literal)),* $(,)?) => {
-        /// Advent of Code solutions for 2025
-        #[derive(Parser)]
-        #[command(name = "aoc", author, version)]
-        #[command(help_template = "\
-            {name} {version} by {author}
-            {about-section}\n{usage-heading} {usage}\n\n{all-args}{tab}")]
-        pub(crate) struct Cli {
-            #[command(subcommand)]
-            pub command: Commands,
-        }
-
-        impl Cli {
-            pub fn run() -> Result<()> {
-                let command = Self::parse().command;
-                command.run()
+use anyhow::{Context, Result, anyhow};
+use aoc_plumbing::{
+    Answer, DynProblem, Params, ParseError, SolveContext, Timeout, dynamic::DynSolveReport,
+    example::Part, parallel::SOLVER_STACK_SIZE, validate::Diagnostic, visualize::Format,
+};
+use aoc_solutions::{WORKSPACE_YEAR, registry};
+use clap::{
+    ArgMatches, Args, Command, CommandFactory, FromArgMatches, Parser, Subcommand,
+    builder::PossibleValuesParser,
+};
+use clap_complete::{
+    CompleteEnv,
+    engine::{
+        ArgValueCandidates, ArgValueCompleter, CompletionCandidate, PathCompleter, ValueCompleter,
+    },
+    env::Shells,
+};
+use serde::Serialize;
+
+use crate::output::{self, Output};
+
+/// Advent of Code solutions
+#[derive(Parser)]
+#[command(name = "aoc", author, version)]
+#[command(help_template = "\
+    {name} {version} by {author}
+    {about-section}\n{usage-heading} {usage}\n\n{all-args}{tab}")]
+pub(crate) struct Cli {
+    /// The year of the solutions to use, e.g. `aoc 2024 run 5`.
+    ///
+    /// This may be specified instead by setting the `AOC_YEAR` env var.
+    /// An explicitly passed value will take precendence over the env
+    /// var. Defaults to the year of the workspace, as given by its `YEAR`
+    /// file.
+    #[clap(env = "AOC_YEAR", add = ArgValueCandidates::new(complete_years))]
+    pub year: Option<usize>,
+
+    #[command(subcommand)]
+    pub command: Commands,
+}
+
+impl Cli {
+    pub fn run() -> Result<()> {
+        // answers the shell when it asks for completions, see
+        // [GenerateCompletions]
+        CompleteEnv::with_factory(Self::command).complete();
+
+        let cli = Self::parse();
+        cli.command.run(cli.year.unwrap_or(WORKSPACE_YEAR))
+    }
+}
+
+/// The day-specific subcommands are generated from the registry at runtime,
+/// so this cannot be derived. Days are named by their title, which is looked
+/// up within the selected year.
+pub(crate) enum Commands {
+    Day(String, Solver),
+    Builtin(Builtin),
+}
+
+impl Commands {
+    pub fn run(&self, year: usize) -> Result<()> {
+        match self {
+            Self::Day(name, cmd) => {
+                let problem = registry()
+                    .iter_year(year)
+                    .find(|p| command_name(*p) == *name)
+                    .with_context(|| format!("{} has no day named '{}'", year, name))?;
+                cmd.run(problem)
             }
+            Self::Builtin(cmd) => cmd.run(year),
+        }
+    }
+}
+
+impl FromArgMatches for Commands {
+    fn from_arg_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+        if let Some((name, sub_matches)) = matches.subcommand()
+            && find_by_command_name(name).is_some()
+        {
+            return Ok(Self::Day(
+                name.to_string(),
+                Solver::from_arg_matches(sub_matches)?,
+            ));
         }
 
-        #[derive(Subcommand)]
-        pub(crate) enum Commands {
-            $(
-            #[command(about = $name::problem_label(), long_about = $name::long_description(), display_order = $day)]
-            $name(Solver<$name>),
-            )*
+        Builtin::from_arg_matches(matches).map(Self::Builtin)
+    }
 
-            #[command(display_order = 30)]
-            Run(Run),
+    fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), clap::Error> {
+        *self = Self::from_arg_matches(matches)?;
+        Ok(())
+    }
+}
 
-            #[command(display_order = 31)]
-            GenerateCompletions(GenerateCompletions),
+impl Subcommand for Commands {
+    fn augment_subcommands(cmd: Command) -> Command {
+        // a title shared by several years gets a single subcommand, described
+        // by its most recent year
+        let mut problems = BTreeMap::new();
+        for problem in registry().iter() {
+            problems.insert(command_name(problem), problem);
         }
 
-        impl Commands {
-            pub fn run(&self) -> Result<()> {
-                match self {
-                    Self::GenerateCompletions(cmd) => cmd.run(),
-                    Self::Run(cmd) => cmd.run(),
-                    $(
-                    Self::$name(cmd) => cmd.run(),
-                    )*
-                }
+        let cmd = problems.into_values().fold(cmd, |cmd, problem| {
+            let (year, day) = (problem.year(), problem.day());
+            cmd.subcommand(
+                Solver::augment_args(
+                    Command::new(command_name(problem))
+                        .about(problem.problem_label())
+                        .long_about(problem.long_description())
+                        .display_order(day),
+                )
+                .mut_arg("input", |arg| {
+                    arg.add(ArgValueCompleter::new(move |current: &OsStr| {
+                        complete_inputs_of(year, [day], current)
+                    }))
+                }),
+            )
+        });
+
+        Builtin::augment_subcommands(cmd)
+    }
+
+    fn augment_subcommands_for_update(cmd: Command) -> Command {
+        Self::augment_subcommands(cmd)
+    }
+
+    fn has_subcommand(name: &str) -> bool {
+        find_by_command_name(name).is_some() || Builtin::has_subcommand(name)
+    }
+}
+
+/// The name of the subcommand for a given problem, i.e. "secret entrance"
+/// becomes `secret-entrance`.
+fn command_name(problem: &dyn DynProblem) -> String {
+    problem.title().replace(' ', "-")
+}
+
+fn find_by_command_name(name: &str) -> Option<&'static dyn DynProblem> {
+    registry().iter().find(|p| command_name(*p) == name)
+}
+
+#[derive(Subcommand)]
+pub(crate) enum Builtin {
+    #[command(display_order = 30)]
+    Run(Run),
+
+    #[command(display_order = 31)]
+    All(All),
+
+    #[command(display_order = 32)]
+    Check(Check),
+
+    #[cfg(feature = "explain")]
+    #[command(display_order = 33)]
+    Explain(Explain),
+
+    #[command(display_order = 34)]
+    Visualize(Visualize),
+
+    #[command(display_order = 35)]
+    Generate(Generate),
+
+    #[command(display_order = 36)]
+    GenerateCompletions(GenerateCompletions),
+}
+
+impl Builtin {
+    pub fn run(&self, year: usize) -> Result<()> {
+        match self {
+            Self::All(cmd) => cmd.run(year),
+            Self::Check(cmd) => cmd.run(year),
+            #[cfg(feature = "explain")]
+            Self::Explain(cmd) => cmd.run(year),
+            Self::Generate(cmd) => cmd.run(year),
+            Self::GenerateCompletions(cmd) => cmd.run(),
+            Self::Run(cmd) => cmd.run(year),
+            Self::Visualize(cmd) => cmd.run(year),
+        }
+    }
+}
+
+/// Run the solution for a specified day with a specified input.
+///
+/// The day must be implemented and the specified input must exist.
+#[derive(Args)]
+pub(crate) struct Run {
+    /// The day to run.
+    ///
+    /// This may be specified instead by setting the `AOC_DAY` env var.
+    /// An explicitly passed value will take precendence over the env
+    /// var.
+    #[clap(env = "AOC_DAY", add = ArgValueCandidates::new(complete_days))]
+    day: usize,
+
+    /// The path to the input for this solution, or `-` for stdin. Defaults to
+    /// stdin when something is piped in, and to the `input.txt` of the day
+    /// within the workspace otherwise.
+    ///
+    /// This may be specified instead by setting the `AOC_INPUT` env
+    /// var. An explicitly passed value will take precendence over the
+    /// env var.
+    #[clap(env = "AOC_INPUT", add = ArgValueCompleter::new(complete_inputs))]
+    input: Option<PathBuf>,
+
+    /// Use an example instead, e.g. `--example` for the day's example.txt or
+    /// `--example 2` for its example2.txt. This takes precedence over an
+    /// input path.
+    #[clap(short, long, value_name = "N", num_args = 0..=1, default_missing_value = "1")]
+    example: Option<usize>,
+
+    /// One of plaintext, json, jsonl, yaml, toml, csv or markdown.
+    /// Defaults to plaintext.
+    ///
+    /// This may be specified instead by setting the `AOC_FORMAT` env
+    /// var. An explicitly passed value will take precendence over the
+    /// env var.
+    #[clap(short, long, env = "AOC_FORMAT", value_parser = parse_output_format)]
+    format: Option<output::Format>,
+
+    /// Display the output as json, the same as `--format json`.
+    ///
+    /// This may be specified instead by setting the `AOC_JSON` env
+    /// var to `true`. If the flag is passed, on the command line, it
+    /// will take precendence over the env var. `--format` takes
+    /// precedence over both.
+    #[clap(short, long, env = "AOC_JSON")]
+    json: bool,
+
+    /// Display how long parsing and each part took.
+    ///
+    /// This may be specified instead by setting the `AOC_TIME` env var
+    /// to `true`. If the flag is passed, on the command line, it will
+    /// take precendence over the env var.
+    #[clap(short, long, env = "AOC_TIME")]
+    time: bool,
+
+    /// Override a puzzle parameter, e.g. `--param connections=10`.
+    ///
+    /// This may be passed multiple times.
+    #[clap(short, long = "param", value_name = "KEY=VALUE", value_parser = parse_param)]
+    params: Vec<(String, String)>,
+
+    /// Give up if solving takes longer than this, e.g. `500ms`, `10s` or
+    /// `2m`. Bare numbers are taken as seconds.
+    ///
+    /// This may be specified instead by setting the `AOC_TIMEOUT` env
+    /// var. An explicitly passed value will take precendence over the
+    /// env var.
+    #[clap(long, env = "AOC_TIMEOUT", value_parser = parse_timeout)]
+    timeout: Option<Duration>,
+
+    /// Only solve this part, either 1 or 2.
+    ///
+    /// This may be specified instead by setting the `AOC_PART` env var.
+    /// An explicitly passed value will take precendence over the env
+    /// var.
+    #[clap(long, env = "AOC_PART", value_parser = parse_part)]
+    part: Option<Part>,
+}
+
+impl Run {
+    pub fn run(&self, year: usize) -> Result<()> {
+        match registry().get(year, self.day) {
+            Some(problem) => _run(
+                problem,
+                &resolve_input(year, self.day, self.input.as_deref(), self.example)?,
+                Params::from_iter(self.params.clone()),
+                output::Format::resolve(self.format, self.json),
+                self.time,
+                self.timeout,
+                self.part,
+            ),
+            None => output::print(
+                &NotImplemented,
+                output::Format::resolve(self.format, self.json),
+            ),
+        }
+    }
+}
+
+#[derive(Args)]
+pub(crate) struct Solver {
+    /// The path to the input for this solution, or `-` for stdin. Defaults to
+    /// stdin when something is piped in, and to the `input.txt` of the day
+    /// within the workspace otherwise.
+    input: Option<PathBuf>,
+
+    /// Use an example instead, e.g. `--example` for the day's example.txt or
+    /// `--example 2` for its example2.txt. This takes precedence over an
+    /// input path.
+    #[clap(short, long, value_name = "N", num_args = 0..=1, default_missing_value = "1")]
+    example: Option<usize>,
+
+    /// One of plaintext, json, jsonl, yaml, toml, csv or markdown.
+    /// Defaults to plaintext.
+    #[clap(short, long, value_parser = parse_output_format)]
+    format: Option<output::Format>,
+
+    /// Display the output as json, the same as `--format json`.
+    #[clap(short, long)]
+    json: bool,
+
+    /// Display how long parsing and each part took.
+    #[clap(short, long)]
+    time: bool,
+
+    /// Override a puzzle parameter, e.g. `--param connections=10`.
+    ///
+    /// This may be passed multiple times.
+    #[clap(short, long = "param", value_name = "KEY=VALUE", value_parser = parse_param)]
+    params: Vec<(String, String)>,
+
+    /// Give up if solving takes longer than this, e.g. `500ms`, `10s` or
+    /// `2m`. Bare numbers are taken as seconds.
+    #[clap(long, value_parser = parse_timeout)]
+    timeout: Option<Duration>,
+
+    /// Only solve this part, either 1 or 2.
+    #[clap(long, value_parser = parse_part)]
+    part: Option<Part>,
+}
+
+impl Solver {
+    pub fn run(&self, problem: &'static dyn DynProblem) -> Result<()> {
+        let input = resolve_input(
+            problem.year(),
+            problem.day(),
+            self.input.as_deref(),
+            self.example,
+        )?;
+        _run(
+            problem,
+            &input,
+            Params::from_iter(self.params.clone()),
+            output::Format::resolve(self.format, self.json),
+            self.time,
+            self.timeout,
+            self.part,
+        )
+    }
+}
+
+/// Run every day of the year against its own input, and summarize them.
+///
+/// Each day's input is the `input.txt` in its directory within the
+/// workspace. Days without one are skipped. Fails if any day fails to solve.
+#[derive(Args)]
+pub(crate) struct All {
+    /// One of plaintext, json, jsonl, yaml, toml, csv or markdown. The
+    /// line based formats have a record per day. Defaults to plaintext.
+    #[clap(short, long, value_parser = parse_output_format)]
+    format: Option<output::Format>,
+
+    /// Print the summary as JSON, the same as `--format json`.
+    #[clap(short, long)]
+    json: bool,
+
+    /// Give up on a day if solving it takes longer than this, e.g. `500ms`,
+    /// `10s` or `2m`. Bare numbers are taken as seconds.
+    #[clap(long, value_parser = parse_timeout)]
+    timeout: Option<Duration>,
+}
+
+impl All {
+    pub fn run(&self, year: usize) -> Result<()> {
+        let root = workspace_root().context("Could not find the root of the workspace")?;
+        let days: Vec<_> = registry()
+            .iter_year(year)
+            .map(|problem| self.solve(problem, &root))
+            .collect();
+        if days.is_empty() {
+            return Err(anyhow!("No days of {} are registered", year));
+        }
+
+        let summary = Summary {
+            year,
+            total: days
+                .iter()
+                .filter_map(|day| day.report.as_ref())
+                .map(|report| report.timings.total())
+                .sum(),
+            days,
+        };
+
+        output::print(&summary, output::Format::resolve(self.format, self.json))?;
+
+        match summary.failures() {
+            0 => Ok(()),
+            1 => Err(anyhow!("1 day failed")),
+            failures => Err(anyhow!("{} days failed", failures)),
+        }
+    }
+
+    fn solve(&self, problem: &'static dyn DynProblem, root: &Path) -> DaySummary {
+        let mut summary = DaySummary {
+            day: problem.day(),
+            title: problem.title(),
+            status: Status::Ok,
+            error: None,
+            report: None,
+        };
+
+        let Some(path) = aoc_solutions::input_path(root, problem.year(), problem.day()) else {
+            summary.status = Status::NoInput;
+            return summary;
+        };
+
+        let result = std::fs::read_to_string(path)
+            .context("Could not read input file")
+            .and_then(|input| {
+                solve_within(self.timeout, move || {
+                    problem.solve_parallel_timed_with(&input, &Params::default())
+                })
+            });
+
+        match result {
+            Ok(report) => summary.report = Some(report),
+            Err(e) => {
+                summary.status = match e.chain().any(|e| e.is::<Timeout>()) {
+                    true => Status::TimedOut,
+                    false => Status::Failed,
+                };
+                summary.error = Some(format!("{:#}", e));
             }
         }
 
-        /// Run the solution for a specified day with a specified input.
-        ///
-        /// The day must be implemented and the specified input must exist.
-        #[derive(Args)]
-        pub(crate) struct Run {
-            /// The day to run.
-            ///
-            /// This may be specified instead by setting the `AOC_DAY` env var.
-            /// An explicitly passed value will take precendence over the env
-            /// var.
-            #[clap(env = "AOC_DAY")]
-            day: usize,
-
-            /// The path to the input for this solution.
-            ///
-            /// This may be specified instead by setting the `AOC_INPUT` env
-            /// var. An explicitly passed value will take precendence over the
-            /// env var.
-            #[clap(env = "AOC_INPUT")]
-            input: PathBuf,
-
-            /// Display the output as json.
-            ///
-            /// This may be specified instead by setting the `AOC_INPUT` env
-            /// var to `true`. If the flag is passed, on the command line, it
-            /// will take precendence over the env var.
-            #[clap(short, long, env = "AOC_JSON")]
-            json: bool,
-        }
-
-        impl Run {
-            pub fn run(&self) -> Result<()> {
-                match self.day {
-                    $(
-                    $day => _run::<$name>(&self.input, self.json),
-                    )*
-                    _ => {
-                        if self.json {
-                            println!("\"not implemented\"");
-                        } else {
-                            println!("not implemented");
-                        }
-                        Ok(())
+        summary
+    }
+}
+
+/// The outcome of solving every day of a year, see [All].
+#[derive(Serialize)]
+struct Summary {
+    year: usize,
+    days: Vec<DaySummary>,
+    #[serde(rename = "total_ns", serialize_with = "serialize_nanos")]
+    total: Duration,
+}
+
+impl Output for Summary {
+    const NAME: &'static str = "summary";
+
+    fn records(&self) -> Result<Vec<serde_json::Value>> {
+        self.days
+            .iter()
+            .map(|day| serde_json::to_value(day).map_err(Into::into))
+            .collect()
+    }
+}
+
+impl Summary {
+    fn failures(&self) -> usize {
+        self.days
+            .iter()
+            .filter(|day| matches!(day.status, Status::Failed | Status::TimedOut))
+            .count()
+    }
+}
+
+#[derive(Serialize)]
+struct DaySummary {
+    day: usize,
+    title: &'static str,
+    status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(flatten)]
+    report: Option<DynSolveReport>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Ok,
+    NoInput,
+    Failed,
+    TimedOut,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ok => write!(f, "ok"),
+            Self::NoInput => write!(f, "no input"),
+            Self::Failed => write!(f, "failed"),
+            Self::TimedOut => write!(f, "timed out"),
+        }
+    }
+}
+
+/// A table of every day, followed by the total time spent solving them and
+/// the errors of any that failed.
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const HEADER: [&str; 8] = [
+            "day",
+            "title",
+            "part 1",
+            "part 2",
+            "parse",
+            "part 1 time",
+            "part 2 time",
+            "status",
+        ];
+
+        let rows: Vec<[String; 8]> = self
+            .days
+            .iter()
+            .map(|day| {
+                let (answers, timings) = match &day.report {
+                    Some(report) => {
+                        let (t, solution) = (&report.timings, &report.solution);
+                        let time = |d: Duration| format!("{:.2?}", d);
+                        // parts the day does not have are left blank
+                        let part = |answer: &Answer, d: Duration| match answer.is_missing() {
+                            true => Default::default(),
+                            false => (answer.to_string(), time(d)),
+                        };
+                        let (part_one, part_one_time) = part(&solution.part_one, t.part_one);
+                        let (part_two, part_two_time) = part(&solution.part_two, t.part_two);
+                        (
+                            [part_one, part_two],
+                            [time(t.parse), part_one_time, part_two_time],
+                        )
                     }
-                }
+                    None => (Default::default(), Default::default()),
+                };
+                let [part_one, part_two] = answers;
+                let [parse, part_one_time, part_two_time] = timings;
+
+                [
+                    format!("{:03}", day.day),
+                    day.title.to_string(),
+                    part_one,
+                    part_two,
+                    parse,
+                    part_one_time,
+                    part_two_time,
+                    day.status.to_string(),
+                ]
+            })
+            .collect();
+
+        let mut widths = HEADER.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let header = HEADER.map(String::from);
+        for row in std::iter::once(&header).chain(&rows) {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        let solved = self.days.iter().filter(|day| day.status == Status::Ok);
+        write!(
+            f,
+            "\ntotal: {:.2?} ({} of {} days solved)",
+            self.total,
+            solved.count(),
+            self.days.len()
+        )?;
+
+        for day in &self.days {
+            if let Some(error) = &day.error {
+                write!(f, "\n{:03} {}: {}", day.day, day.title, error)?;
             }
         }
+
+        Ok(())
+    }
+}
+
+fn serialize_nanos<S: serde::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_nanos() as u64)
+}
+
+/// Where an input is read from.
+enum Source {
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    /// The name of the input in diagnostics.
+    fn name(&self) -> String {
+        match self {
+            Self::File(path) => path.display().to_string(),
+            Self::Stdin => "<stdin>".to_string(),
+        }
+    }
+
+    fn read_to_string(&self) -> Result<String> {
+        match self {
+            Self::File(path) => std::fs::read_to_string(path).context("Could not read input file"),
+            Self::Stdin => {
+                std::io::read_to_string(std::io::stdin()).context("Could not read stdin")
+            }
+        }
+    }
+}
+
+/// The input to use for the given day: the requested example of the day if
+/// there is one, then the given input, where `-` is stdin, then stdin if
+/// something is [piped in](stdin_is_piped), and the real input of the day
+/// otherwise. Inputs of the day are found within the workspace.
+fn resolve_input(
+    year: usize,
+    day: usize,
+    input: Option<&Path>,
+    example: Option<usize>,
+) -> Result<Source> {
+    let root = workspace_root();
+    let root = root.as_deref();
+
+    match (example, input) {
+        (Some(n), _) => root
+            .and_then(|root| aoc_solutions::example_path(root, year, day, n))
+            .map(Source::File)
+            .with_context(|| format!("no example {} for day {}", n, day)),
+        (None, Some(input)) if input == Path::new("-") => Ok(Source::Stdin),
+        (None, Some(input)) => Ok(Source::File(input.to_path_buf())),
+        (None, None) if stdin_is_piped() => Ok(Source::Stdin),
+        (None, None) => root
+            .and_then(|root| aoc_solutions::input_path(root, year, day))
+            .map(Source::File)
+            .with_context(|| format!("no input for day {}", day)),
+    }
+}
+
+/// Whether stdin is a pipe or a non-empty file. Merely not being a terminal
+/// is not enough, as stdin is `/dev/null` or similar under CI, cron, `nohup`
+/// and the like, where the input of the day is wanted instead.
+#[cfg(unix)]
+fn stdin_is_piped() -> bool {
+    use std::os::{fd::AsFd, unix::fs::FileTypeExt};
+
+    let Ok(fd) = std::io::stdin().as_fd().try_clone_to_owned() else {
+        return false;
+    };
+
+    File::from(fd).metadata().is_ok_and(|metadata| {
+        metadata.file_type().is_fifo() || (metadata.is_file() && metadata.len() > 0)
+    })
+}
+
+/// Whether stdin is a pipe or a non-empty file, which cannot be told apart
+/// from other kinds of stdin here, so `-` is needed to read stdin.
+#[cfg(not(unix))]
+fn stdin_is_piped() -> bool {
+    false
+}
+
+/// The root of the workspace the current directory is in, or that this
+/// binary was built from if it is not in one.
+fn workspace_root() -> Option<PathBuf> {
+    let is_root = |dir: &Path| {
+        std::fs::read_to_string(dir.join("Cargo.toml")).is_ok_and(|s| s.contains("[workspace]"))
     };
+
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| {
+            cwd.ancestors()
+                .find(|dir| is_root(dir))
+                .map(Path::to_path_buf)
+        })
+        .or_else(|| {
+            let built_from = Path::new(env!("CARGO_MANIFEST_DIR")).parent()?;
+            is_root(built_from).then(|| built_from.to_path_buf())
+        })
+}
+
+/// The year completions are for: the year already on the command line, as
+/// in `aoc 2024 run <TAB>`, then `AOC_YEAR`, then the year of the workspace.
+fn completion_year() -> usize {
+    typed_year()
+        .or_else(|| {
+            std::env::var("AOC_YEAR")
+                .ok()
+                .and_then(|year| year.parse().ok())
+        })
+        .unwrap_or(WORKSPACE_YEAR)
 }
 
+/// The year on the command line being completed, which the shell passes to
+/// [CompleteEnv] after a `--`, as in `aoc -- aoc 2024 run`. The year is the
+/// only argument that can come straight after the name of the binary.
+fn typed_year() -> Option<usize> {
+    std::env::args_os()
+        .skip_while(|arg| arg != "--")
+        .nth(2)
+        .and_then(|year| year.to_str()?.parse().ok())
+}
+
+fn complete_years() -> Vec<CompletionCandidate> {
+    registry()
+        .years()
+        .map(|year| CompletionCandidate::new(year.to_string()))
+        .collect()
+}
+
+/// Completes the days of the year, described by their title.
+fn complete_days() -> Vec<CompletionCandidate> {
+    let year = completion_year();
+    registry()
+        .iter_year(year)
+        .map(|problem| {
+            CompletionCandidate::new(problem.day().to_string()).help(Some(problem.title().into()))
+        })
+        .collect()
+}
+
+/// Completes the inputs of every day of the year.
+fn complete_inputs(current: &OsStr) -> Vec<CompletionCandidate> {
+    let year = completion_year();
+    complete_inputs_of(year, registry().days(year), current)
+}
+
+/// Completes the `.txt` files in the directories of the given days within
+/// the workspace, followed by any other file, and `-` for stdin. Inputs are
+/// relative to the current directory if they are within it.
+fn complete_inputs_of(
+    year: usize,
+    days: impl IntoIterator<Item = usize>,
+    current: &OsStr,
+) -> Vec<CompletionCandidate> {
+    let cwd = std::env::current_dir().ok();
+    let current_str = current.to_string_lossy();
+    let mut inputs = Vec::new();
+
+    if let Some(root) = workspace_root() {
+        for day in days {
+            let Some(entries) = aoc_solutions::day_dir(&root, year, day)
+                .and_then(|dir| std::fs::read_dir(dir).ok())
+            else {
+                continue;
+            };
+
+            let mut paths: Vec<_> = entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "txt"))
+                .collect();
+            paths.sort();
+
+            for path in paths {
+                let path = match cwd.as_deref().and_then(|cwd| path.strip_prefix(cwd).ok()) {
+                    Some(relative) => relative.to_path_buf(),
+                    None => path,
+                };
+                if path.to_string_lossy().starts_with(&*current_str) {
+                    inputs.push(
+                        CompletionCandidate::new(path)
+                            .help(Some(format!("an input of day {}", day).into())),
+                    );
+                }
+            }
+        }
+    }
+
+    for candidate in PathCompleter::file().stdio().complete(current) {
+        if !inputs
+            .iter()
+            .any(|input| input.get_value() == candidate.get_value())
+        {
+            inputs.push(candidate);
+        }
+    }
+
+    inputs
+}
+
+/// Check an input for a specified day without solving it.
+///
+/// Reports anything that would make solving the input fail, or produce
+/// nonsense. Fails if the input does not parse or any error is found,
+/// warnings alone do not.
 #[derive(Args)]
-pub(crate) struct Solver<T>
-where
-    T: Problem,
-{
-    /// The path to the input for this solution.
-    input: PathBuf,
+pub(crate) struct Check {
+    /// The day to check the input of.
+    #[clap(add = ArgValueCandidates::new(complete_days))]
+    day: usize,
+
+    /// The path to the input to check, or `-` for stdin. Defaults to
+    /// stdin when something is piped in, and to the `input.txt` of the day
+    /// within the workspace otherwise.
+    #[clap(add = ArgValueCompleter::new(complete_inputs))]
+    input: Option<PathBuf>,
+
+    /// Use an example instead, e.g. `--example` for the day's example.txt or
+    /// `--example 2` for its example2.txt. This takes precedence over an
+    /// input path.
+    #[clap(short, long, value_name = "N", num_args = 0..=1, default_missing_value = "1")]
+    example: Option<usize>,
+
+    /// One of plaintext, json, jsonl, yaml, toml, csv or markdown. The
+    /// line based formats have a record per problem. Defaults to plaintext.
+    #[clap(short, long, value_parser = parse_output_format)]
+    format: Option<output::Format>,
 
-    /// Display the output as json.
+    /// Print the problems found as a JSON array, the same as `--format json`.
     #[clap(short, long)]
     json: bool,
 
-    #[clap(skip)]
-    _phantom: PhantomData<T>,
+    /// Override a puzzle parameter, e.g. `--param connections=10`.
+    ///
+    /// This may be passed multiple times.
+    #[clap(short, long = "param", value_name = "KEY=VALUE", value_parser = parse_param)]
+    params: Vec<(String, String)>,
 }
 
-impl<T> Solver<T>
-where
-    T: Problem,
-    <T as Problem>::ProblemError: Into<anyhow::Error>,
-{
-    pub fn run(&self) -> Result<()> {
-        _run::<T>(&self.input, self.json)
+impl Check {
+    pub fn run(&self, year: usize) -> Result<()> {
+        let problem = registry()
+            .get(year, self.day)
+            .with_context(|| format!("Day {} of {} is not registered", self.day, year))?;
+        let source = resolve_input(year, self.day, self.input.as_deref(), self.example)?;
+        let input = source.read_to_string()?;
+        let diagnostics = problem
+            .validate_with(&input, &Params::from_iter(self.params.clone()))
+            .map_err(|e| diagnose(e, &source.name(), "Failed to check"))?;
+
+        let diagnostics = Diagnostics(diagnostics);
+        output::print(
+            &diagnostics,
+            output::Format::resolve(self.format, self.json),
+        )?;
+
+        match diagnostics.0.iter().filter(|d| d.is_error()).count() {
+            0 => Ok(()),
+            1 => Err(anyhow!("The input has an error")),
+            errors => Err(anyhow!("The input has {} errors", errors)),
+        }
+    }
+}
+
+/// Trace the steps a solution takes for a specified day and input.
+///
+/// Each step is printed on its own line, followed by the solution. Which
+/// steps are traced depends on the day, days that do not trace anything just
+/// print the solution.
+#[cfg(feature = "explain")]
+#[derive(Args)]
+pub(crate) struct Explain {
+    /// The day to explain.
+    #[clap(add = ArgValueCandidates::new(complete_days))]
+    day: usize,
+
+    /// The path to the input for this solution, or `-` for stdin. Defaults to
+    /// stdin when something is piped in, and to the `input.txt` of the day
+    /// within the workspace otherwise.
+    #[clap(add = ArgValueCompleter::new(complete_inputs))]
+    input: Option<PathBuf>,
+
+    /// Use an example instead, e.g. `--example` for the day's example.txt or
+    /// `--example 2` for its example2.txt. This takes precedence over an
+    /// input path.
+    #[clap(short, long, value_name = "N", num_args = 0..=1, default_missing_value = "1")]
+    example: Option<usize>,
+
+    /// Print the steps, and the solution, as JSON lines.
+    #[clap(short, long)]
+    json: bool,
+
+    /// Override a puzzle parameter, e.g. `--param connections=10`.
+    ///
+    /// This may be passed multiple times.
+    #[clap(short, long = "param", value_name = "KEY=VALUE", value_parser = parse_param)]
+    params: Vec<(String, String)>,
+
+    /// Write the steps to this path instead of stdout. The solution is still
+    /// printed to stdout.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[cfg(feature = "explain")]
+impl Explain {
+    pub fn run(&self, year: usize) -> Result<()> {
+        use std::{
+            cell::RefCell,
+            io::{BufWriter, Write},
+            rc::Rc,
+        };
+
+        use aoc_plumbing::trace::{self, Event};
+
+        let problem = registry()
+            .get(year, self.day)
+            .with_context(|| format!("Day {} of {} is not registered", self.day, year))?;
+        let source = resolve_input(year, self.day, self.input.as_deref(), self.example)?;
+        let input = source.read_to_string()?;
+        let params = Params::from_iter(self.params.clone());
+
+        let out: Box<dyn Write> = match &self.output {
+            Some(path) => Box::new(
+                File::create(path)
+                    .with_context(|| format!("Could not create {}", path.display()))?,
+            ),
+            None => Box::new(std::io::stdout()),
+        };
+        let out = Rc::new(RefCell::new(BufWriter::new(out)));
+        // the first write error is kept, and anything after it is dropped
+        let error = Rc::new(RefCell::new(None));
+
+        let json = self.json;
+        let (tracer_out, tracer_error) = (out.clone(), error.clone());
+        let tracer = move |event: Event| {
+            if tracer_error.borrow().is_some() {
+                return;
+            }
+
+            let line = match json {
+                true => serde_json::to_string(&event).map_err(Into::into),
+                false => Ok(match event.phase {
+                    Some(phase) => format!("{:>8}  {}", phase.name(), event),
+                    None => event.to_string(),
+                }),
+            };
+            let result = line.and_then(|line| writeln!(tracer_out.borrow_mut(), "{}", line));
+
+            if let Err(e) = result {
+                *tracer_error.borrow_mut() = Some(e);
+            }
+        };
+
+        let solution = SolveContext::default()
+            .run(|| trace::run(tracer, || problem.solve_with(&input, &params)))
+            .map_err(|e| diagnose(e, &source.name(), "Failed to solve"))?;
+
+        if let Some(e) = error.take() {
+            return Err(e).context("Could not write the trace");
+        }
+        out.borrow_mut()
+            .flush()
+            .context("Could not write the trace")?;
+
+        output::print(&solution, output::Format::resolve(None, self.json))
+    }
+}
+
+/// Render a visualization of the input for a specified day.
+///
+/// Only some days can be visualized.
+#[derive(Args)]
+pub(crate) struct Visualize {
+    /// The day to visualize.
+    #[clap(add = ArgValueCandidates::new(complete_days))]
+    day: usize,
+
+    /// The path to the input to visualize, or `-` for stdin. Defaults to
+    /// stdin when something is piped in, and to the `input.txt` of the day
+    /// within the workspace otherwise.
+    #[clap(add = ArgValueCompleter::new(complete_inputs))]
+    input: Option<PathBuf>,
+
+    /// Use an example instead, e.g. `--example` for the day's example.txt or
+    /// `--example 2` for its example2.txt. This takes precedence over an
+    /// input path.
+    #[clap(short, long, value_name = "N", num_args = 0..=1, default_missing_value = "1")]
+    example: Option<usize>,
+
+    /// One of ansi, svg, ppm or png.
+    ///
+    /// Defaults to the extension of the output path if there is one, and to
+    /// ansi otherwise.
+    #[clap(short, long, value_parser = parse_format)]
+    format: Option<Format>,
+
+    /// Override a puzzle parameter, e.g. `--param connections=10`.
+    ///
+    /// This may be passed multiple times.
+    #[clap(short, long = "param", value_name = "KEY=VALUE", value_parser = parse_param)]
+    params: Vec<(String, String)>,
+
+    /// Write the rendering to this path instead of stdout.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+}
+
+impl Visualize {
+    pub fn run(&self, year: usize) -> Result<()> {
+        use std::io::Write;
+
+        let visualizer = registry()
+            .visualizer(year, self.day)
+            .with_context(|| format!("Day {} of {} has no visualization", self.day, year))?;
+        let input =
+            resolve_input(year, self.day, self.input.as_deref(), self.example)?.read_to_string()?;
+        let scene = visualizer.visualize(&input, &Params::from_iter(self.params.clone()))?;
+
+        let format = match (self.format, &self.output) {
+            (Some(format), _) => format,
+            (None, Some(path)) => path
+                .extension()
+                .and_then(|ext| ext.to_str()?.parse().ok())
+                .unwrap_or_default(),
+            (None, None) => Format::default(),
+        };
+        let rendering = scene.render(format)?;
+
+        match &self.output {
+            Some(path) => std::fs::write(path, rendering)
+                .with_context(|| format!("Could not write {}", path.display())),
+            None => std::io::stdout()
+                .write_all(&rendering)
+                .context("Could not write to stdout"),
+        }
+    }
+}
+
+/// Generate a random input for a specified day.
+///
+/// The same seed and parameters always generate the same input.
+#[derive(Args)]
+pub(crate) struct Generate {
+    /// The day to generate an input for.
+    #[clap(add = ArgValueCandidates::new(complete_days))]
+    day: usize,
+
+    /// The seed for the random number generator.
+    #[clap(short, long, default_value_t = 0)]
+    seed: u64,
+
+    /// Override a size parameter, e.g. `--param banks=10`.
+    ///
+    /// This may be passed multiple times.
+    #[clap(short, long = "param", value_name = "KEY=VALUE", value_parser = parse_param)]
+    params: Vec<(String, String)>,
+
+    /// Write the input to this path instead of stdout.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+}
+
+impl Generate {
+    pub fn run(&self, year: usize) -> Result<()> {
+        let generator = registry()
+            .generator(year, self.day)
+            .with_context(|| format!("Day {} of {} has no generator", self.day, year))?;
+        let input = generator.generate(self.seed, &Params::from_iter(self.params.clone()))?;
+
+        match &self.output {
+            Some(path) => std::fs::write(path, input + "\n")
+                .with_context(|| format!("Could not write {}", path.display())),
+            None => {
+                println!("{}", input);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn parse_param(pair: &str) -> Result<(String, String)> {
+    Params::parse_pair(pair)
+}
+
+fn parse_part(part: &str) -> Result<Part> {
+    match part {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(anyhow!("expected 1 or 2")),
+    }
+}
+
+fn parse_format(format: &str) -> Result<Format> {
+    format.parse()
+}
+
+fn parse_output_format(format: &str) -> Result<output::Format> {
+    format.parse()
+}
+
+/// Parses durations like `500ms`, `10s` or `2m`. Bare numbers are seconds.
+fn parse_timeout(timeout: &str) -> Result<Duration> {
+    let (value, unit) = match timeout.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(i) => timeout.split_at(i),
+        None => (timeout, "s"),
+    };
+
+    let value: f64 = value
+        .trim()
+        .parse()
+        .with_context(|| format!("invalid timeout '{}'", timeout))?;
+
+    let scale = match unit {
+        "ms" => 0.001,
+        "s" => 1.0,
+        "m" => 60.0,
+        _ => {
+            return Err(anyhow!(
+                "unknown unit '{}', expected one of: ms, s, m",
+                unit
+            ));
+        }
+    };
+
+    Duration::try_from_secs_f64(value * scale)
+        .with_context(|| format!("invalid timeout '{}'", timeout))
+}
+
+/// Inputs larger than this are streamed to problems that support it, rather
+/// than being read into memory.
+const STREAMING_THRESHOLD: u64 = 256 * 1024 * 1024;
+
+/// How long past its deadline a solve that never polls its context is given
+/// before it is abandoned.
+const TIMEOUT_GRACE: Duration = Duration::from_millis(100);
+
+enum Input {
+    Stream(Box<dyn BufRead + Send>),
+    Text(String),
+}
+
+fn _run(
+    problem: &'static dyn DynProblem,
+    source: &Source,
+    params: Params,
+    format: output::Format,
+    time: bool,
+    timeout: Option<Duration>,
+    part: Option<Part>,
+) -> Result<()> {
+    let input = match source {
+        // the size of stdin is not known up front, so it is treated like any
+        // other input that is not a file
+        Source::Stdin => Input::Stream(Box::new(BufReader::new(std::io::stdin()))),
+        Source::File(path) => {
+            let file = File::open(path).context("Could not read input file")?;
+            let metadata = file.metadata().context("Could not read input file")?;
+            let mut reader = BufReader::new(file);
+
+            if !metadata.is_file() || metadata.len() > STREAMING_THRESHOLD {
+                Input::Stream(Box::new(reader))
+            } else {
+                let mut input = String::new();
+                reader
+                    .read_to_string(&mut input)
+                    .context("Could not read input file")?;
+                Input::Text(input)
+            }
+        }
+    };
+
+    let report = solve_within(timeout, move || solve(problem, input, &params, part))
+        .map_err(|e| diagnose(e, &source.name(), "Failed to solve"))?;
+
+    match part {
+        Some(part) => output::print(&PartReport { part, report, time }, format),
+        None if time => output::print(&report, format),
+        None => output::print(&report.solution, format),
+    }
+}
+
+/// Streamed inputs cannot be parsed without solving, so both parts are
+/// solved even if only one was asked for.
+fn solve(
+    problem: &dyn DynProblem,
+    input: Input,
+    params: &Params,
+    part: Option<Part>,
+) -> Result<DynSolveReport> {
+    match input {
+        Input::Text(input) => match part {
+            Some(part) => problem.solve_part_timed_with(&input, params, part),
+            None => problem.solve_parallel_timed_with(&input, params),
+        },
+        Input::Stream(mut reader) => match problem.solve_reader(&mut reader, params) {
+            Some(report) => report,
+            None => {
+                let mut input = String::new();
+                reader
+                    .read_to_string(&mut input)
+                    .context("Could not read input file")?;
+                solve(problem, Input::Text(input), params, part)
+            }
+        },
     }
 }
 
-fn _run<T>(input_file: &Path, json: bool) -> Result<()>
+/// Runs `solve` on a worker thread within a [SolveContext] with the given
+/// deadline. Solves that do not poll their context are abandoned shortly
+/// after the deadline, reporting the phase they were in.
+fn solve_within<R>(
+    timeout: Option<Duration>,
+    solve: impl FnOnce() -> Result<R> + Send + 'static,
+) -> Result<R>
 where
-    T: Problem,
-    <T as Problem>::ProblemError: Into<anyhow::Error>,
+    R: Send + 'static,
 {
-    let input = std::fs::read_to_string(input_file).context("Could not read input file")?;
+    let Some(timeout) = timeout else {
+        return solve();
+    };
 
-    let solution = T::solve(&input)
-        .map_err(Into::<anyhow::Error>::into)
-        .context("Failed to solve")?;
+    let ctx = SolveContext::with_timeout(timeout);
+    let worker_ctx = ctx.clone();
+    let (tx, rx) = mpsc::channel();
+    let worker = thread::Builder::new()
+        .stack_size(SOLVER_STACK_SIZE)
+        .spawn(move || tx.send(worker_ctx.run(solve)))
+        .context("Could not start solver")?;
 
-    if json {
-        println!("{}", serde_json::to_string(&solution)?);
-    } else {
-        println!("{}", solution);
+    match rx.recv_timeout(timeout + TIMEOUT_GRACE) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => {
+            let err = ctx.timeout();
+            ctx.cancel();
+            Err(err.into())
+        }
+        Err(RecvTimeoutError::Disconnected) => match worker.join() {
+            Err(panic) => std::panic::resume_unwind(panic),
+            Ok(_) => Err(anyhow!("The solver exited without a result")),
+        },
     }
+}
 
-    Ok(())
+/// Replaces parse errors with an annotated diagnostic pointing into the input
+/// named `name`, and describes any other error as a failure to do `action`.
+fn diagnose(err: anyhow::Error, name: &str, action: &'static str) -> anyhow::Error {
+    match err.chain().find_map(|e| e.downcast_ref::<ParseError>()) {
+        Some(parse_error) => anyhow!(parse_error.render(name)),
+        None => err.context(action),
+    }
 }
 
-/// Generate zsh completions
-#[derive(Debug, Args)]
-pub struct GenerateCompletions;
+/// The problems [Check] found, serialized as a plain array of them.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct Diagnostics(Vec<Diagnostic>);
+
+impl Output for Diagnostics {
+    const NAME: &'static str = "diagnostics";
+
+    fn records(&self) -> Result<Vec<serde_json::Value>> {
+        self.0
+            .iter()
+            .map(|diagnostic| serde_json::to_value(diagnostic).map_err(Into::into))
+            .collect()
+    }
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "no problems found");
+        }
+
+        let lines: Vec<_> = self.0.iter().map(Diagnostic::to_string).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// Printed by [Run] for days without a solution.
+struct NotImplemented;
+
+impl Display for NotImplemented {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "not implemented")
+    }
+}
+
+impl Serialize for NotImplemented {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str("not implemented")
+    }
+}
+
+impl Output for NotImplemented {
+    const NAME: &'static str = "status";
+}
+
+/// The answer to a single part, printed in place of the [DynSolveReport]
+/// when only that part was solved.
+struct PartReport {
+    part: Part,
+    report: DynSolveReport,
+    time: bool,
+}
+
+impl PartReport {
+    fn answer(&self) -> &Answer {
+        match self.part {
+            Part::One => &self.report.solution.part_one,
+            Part::Two => &self.report.solution.part_two,
+        }
+    }
+
+    fn key(&self) -> &'static str {
+        match self.part {
+            Part::One => "part_one",
+            Part::Two => "part_two",
+        }
+    }
+
+    fn solve_time(&self) -> Duration {
+        match self.part {
+            Part::One => self.report.timings.part_one,
+            Part::Two => self.report.timings.part_two,
+        }
+    }
+}
+
+impl Display for PartReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.part, self.answer())?;
+
+        if self.time {
+            let (parse, solve) = (self.report.timings.parse, self.solve_time());
+            write!(
+                f,
+                "\n\nparse:  {:?}\n{}: {:?}\ntotal:  {:?}",
+                parse,
+                self.part,
+                solve,
+                parse + solve
+            )?;
+        }
 
-impl GenerateCompletions {
-    fn run(&self) -> Result<()> {
-        generate(Zsh, &mut Cli::command(), "aoc", &mut std::io::stdout());
         Ok(())
     }
 }
 
-generate_cli! {
-    (SecretEntrance, 1),
-    (GiftShop, 2),
-    (Lobby, 3),
-    (PrintingDepartment, 4),
-    (Cafeteria, 5),
-    (TrashCompactor, 6),
-    (Laboratories, 7),
-    (Playground, 8),
-    (Factory, 10),
-    (Reactor, 11),
-    // command_marker
+impl Output for PartReport {
+    const NAME: &'static str = "report";
+}
+
+/// Serialized like a [DynSolveReport] without the part that was not solved.
+impl Serialize for PartReport {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry(self.key(), self.answer())?;
+
+        if self.time {
+            let (parse, solve) = (self.report.timings.parse, self.solve_time());
+            let nanos = |d: Duration| d.as_nanos() as u64;
+            let mut ns = serde_json::Map::default();
+            ns.insert("parse_ns".into(), nanos(parse).into());
+            ns.insert(format!("{}_ns", self.key()), nanos(solve).into());
+            ns.insert("total_ns".into(), nanos(parse + solve).into());
+            map.serialize_entry("timings", &ns)?;
+        }
+
+        map.end()
+    }
+}
+
+/// Generate completions for bash, zsh, fish, elvish or powershell.
+///
+/// The generated script asks `aoc` for completions as they are needed, so
+/// days and their inputs are completed from the solutions and the workspace
+/// at the time. `aoc` needs to be on the `PATH` for this, e.g. by adding
+/// `source <(aoc generate-completions bash)` to `~/.bashrc`.
+#[derive(Debug, Args)]
+pub struct GenerateCompletions {
+    /// The shell to generate completions for.
+    #[clap(value_parser = PossibleValuesParser::new(Shells::builtins().names()))]
+    shell: String,
+}
+
+impl GenerateCompletions {
+    fn run(&self) -> Result<()> {
+        let shells = Shells::builtins();
+        let shell = shells
+            .completer(&self.shell)
+            .with_context(|| format!("Unknown shell '{}'", self.shell))?;
+
+        // the same variable and names that [CompleteEnv] registers with
+        shell
+            .write_registration("COMPLETE", "aoc", "aoc", "aoc", &mut std::io::stdout())
+            .context("Could not write the completions")
+    }
 }
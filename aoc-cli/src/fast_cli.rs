@@ -1,49 +1,45 @@
 use std::env;
 
-use aoc_plumbing::Problem;
-use cafeteria::Cafeteria;
-use factory::Factory;
-use gift_shop::GiftShop;
-use laboratories::Laboratories;
-use lobby::Lobby;
-use playground::Playground;
-use printing_department::PrintingDepartment;
-use reactor::Reactor;
-use secret_entrance::SecretEntrance;
-use trash_compactor::TrashCompactor;
-// import_marker
+use aoc_plumbing::Answer;
+use aoc_solutions::{WORKSPACE_YEAR, registry};
 
-macro_rules! generate_cli {
-    ($(($name:ident, $day:literal)),* $(,)?) => {
-        pub fn run() -> anyhow::Result<()> {
-            let day: u8 = env::var("AOC_DAY")?.parse()?;
-            let input_file = env::var("AOC_INPUT")?;
-            let input = std::fs::read_to_string(&input_file)?;
-            match day {
-                $(
-                $day => {
-                    let sln = $name::solve(&input)?;
-                    println!(r#"{{"part_one": "{}", "part_two": "{}"}}"#, sln.part_one, sln.part_two);
-                },
-                )*
-                _ => { println!("\"not implemented\""); }
-            }
+pub fn run() -> anyhow::Result<()> {
+    let day: usize = env::var("AOC_DAY")?.parse()?;
+    let input_file = env::var("AOC_INPUT")?;
+    let input = if input_file == "-" {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        std::fs::read_to_string(&input_file)?
+    };
+    // the year of the workspace, unless AOC_YEAR says otherwise
+    let year = match env::var("AOC_YEAR") {
+        Ok(year) => year.parse()?,
+        Err(_) => WORKSPACE_YEAR,
+    };
 
-            Ok(())
+    match registry().get(year, day) {
+        Some(problem) => {
+            let sln = problem.solve_parallel(&input)?;
+            println!(
+                r#"{{"part_one": {}, "part_two": {}}}"#,
+                ci_answer(&sln.part_one),
+                ci_answer(&sln.part_two)
+            );
+        }
+        None => {
+            println!("\"not implemented\"");
         }
     }
+
+    Ok(())
 }
 
-generate_cli! {
-    (SecretEntrance, 1),
-    (GiftShop, 2),
-    (Lobby, 3),
-    (PrintingDepartment, 4),
-    (Cafeteria, 5),
-    (TrashCompactor, 6),
-    (Laboratories, 7),
-    (Playground, 8),
-    (Factory, 10),
-    (Reactor, 11),
-    // command_marker
+/// Answers are always emitted as strings, except for missing answers, which
+/// are emitted as `null`.
+fn ci_answer(answer: &Answer) -> String {
+    if answer.is_missing() {
+        "null".to_string()
+    } else {
+        format!("\"{}\"", answer)
+    }
 }
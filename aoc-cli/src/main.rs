@@ -1,5 +1,7 @@
 #[cfg(not(feature = "lite"))]
 mod cli;
+#[cfg(not(feature = "lite"))]
+mod output;
 
 // Avoid musl's default allocator due to lackluster performance
 // https://nickb.dev/blog/default-musl-allocator-considered-harmful-to-performance
@@ -0,0 +1,242 @@
+use std::{fmt::Display, str::FromStr};
+
+use anyhow::{Result, anyhow};
+use aoc_plumbing::dynamic::{DynSolution, DynSolveReport};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// The formats a command's output can be displayed in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum Format {
+    /// The human readable output.
+    #[default]
+    Plaintext,
+    Json,
+    /// One JSON object per record of the output.
+    Jsonl,
+    Yaml,
+    Toml,
+    /// One row per record of the output, with nested fields flattened into
+    /// dotted columns like `timings.parse_ns`.
+    Csv,
+    /// The same table as [Format::Csv], as a Markdown table.
+    Markdown,
+}
+
+impl Format {
+    pub const ALL: [Self; 7] = [
+        Self::Plaintext,
+        Self::Json,
+        Self::Jsonl,
+        Self::Yaml,
+        Self::Toml,
+        Self::Csv,
+        Self::Markdown,
+    ];
+
+    /// The format to use given `--format` and the older `--json` switch,
+    /// where `--format` takes precedence.
+    pub fn resolve(format: Option<Self>, json: bool) -> Self {
+        match (format, json) {
+            (Some(format), _) => format,
+            (None, true) => Self::Json,
+            (None, false) => Self::Plaintext,
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Plaintext => write!(f, "plaintext"),
+            Self::Json => write!(f, "json"),
+            Self::Jsonl => write!(f, "jsonl"),
+            Self::Yaml => write!(f, "yaml"),
+            Self::Toml => write!(f, "toml"),
+            Self::Csv => write!(f, "csv"),
+            Self::Markdown => write!(f, "markdown"),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.to_string() == s)
+            .ok_or_else(|| {
+                anyhow!(
+                    "unknown format '{}', expected one of: \
+                    plaintext, json, jsonl, yaml, toml, csv, markdown",
+                    s
+                )
+            })
+    }
+}
+
+/// Something a command prints, which can be displayed in any [Format].
+pub(crate) trait Output: Display + Serialize {
+    /// What the output is called. Records that are not objects are put in a
+    /// column of this name, and TOML, which needs a table at the top, puts
+    /// outputs that are not one under this key.
+    const NAME: &'static str;
+
+    /// The records the output is made up of, which are the rows of a table
+    /// or the lines of JSON lines. Most outputs are a single record.
+    fn records(&self) -> Result<Vec<Value>> {
+        Ok(vec![serde_json::to_value(self)?])
+    }
+}
+
+impl Output for DynSolution {
+    const NAME: &'static str = "solution";
+}
+
+impl Output for DynSolveReport {
+    const NAME: &'static str = "report";
+}
+
+/// Prints `output` in the given format. Nothing is printed if there is
+/// nothing to display, like JSON lines without any records.
+pub(crate) fn print<T: Output>(output: &T, format: Format) -> Result<()> {
+    let rendered = render(output, format)?;
+    if !rendered.is_empty() {
+        println!("{}", rendered);
+    }
+
+    Ok(())
+}
+
+fn render<T: Output>(output: &T, format: Format) -> Result<String> {
+    let rendered = match format {
+        Format::Plaintext => output.to_string(),
+        Format::Json => serde_json::to_string(output)?,
+        Format::Jsonl => output
+            .records()?
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()?
+            .join("\n"),
+        Format::Yaml => serde_yaml::to_string(output)?,
+        Format::Toml => {
+            let value = match without_nulls(serde_json::to_value(output)?) {
+                value @ Value::Object(_) => value,
+                value => Value::Object(Map::from_iter([(T::NAME.to_string(), value)])),
+            };
+            toml::to_string(&value)?
+        }
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            let (columns, rows) = table(T::NAME, output.records()?);
+            writer.write_record(&columns)?;
+            for row in rows {
+                writer.write_record(&row)?;
+            }
+            String::from_utf8(writer.into_inner()?)?
+        }
+        Format::Markdown => {
+            let (columns, rows) = table(T::NAME, output.records()?);
+            let line = |cells: &[String]| format!("| {} |", cells.join(" | "));
+            let escape = |cell: &String| cell.replace('|', "\\|").replace('\n', "<br>");
+
+            std::iter::once(line(&columns))
+                .chain(std::iter::once(line(&vec!["---".into(); columns.len()])))
+                .chain(
+                    rows.iter()
+                        .map(|row| line(&row.iter().map(escape).collect::<Vec<_>>())),
+                )
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    };
+
+    Ok(rendered.trim_end().to_string())
+}
+
+/// TOML has no null, so fields without a value are left out instead.
+fn without_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k, without_nulls(v)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .filter(|v| !v.is_null())
+                .map(without_nulls)
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+/// Flattens `records` into the columns of a table, in the order they first
+/// appear, and a row of cells per record. Cells a record has no value for are
+/// left empty, and columns no record has a value for, like the part two of a
+/// day without one, are left out.
+fn table(name: &str, records: Vec<Value>) -> (Vec<String>, Vec<Vec<String>>) {
+    let records: Vec<Map<String, Value>> = records
+        .into_iter()
+        .map(|record| {
+            let mut row = Map::new();
+            match record {
+                Value::Object(fields) => {
+                    for (key, value) in fields {
+                        flatten(key, value, &mut row);
+                    }
+                }
+                value => flatten(name.to_string(), value, &mut row),
+            }
+            row
+        })
+        .collect();
+
+    let mut columns: Vec<String> = Vec::new();
+    for column in records.iter().flat_map(Map::keys) {
+        if !columns.contains(column) {
+            columns.push(column.clone());
+        }
+    }
+
+    let rows = records
+        .iter()
+        .map(|record| {
+            columns
+                .iter()
+                .map(|column| match record.get(column) {
+                    None => String::new(),
+                    Some(Value::String(s)) => s.clone(),
+                    Some(value) => value.to_string(),
+                })
+                .collect()
+        })
+        .collect();
+
+    (columns, rows)
+}
+
+/// Nested fields are named after their key, and elements of arrays after
+/// their index, prefixed by the name of what they are nested in.
+fn flatten(name: String, value: Value, row: &mut Map<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                flatten(format!("{}.{}", name, key), value, row);
+            }
+        }
+        Value::Array(values) => {
+            for (i, value) in values.into_iter().enumerate() {
+                flatten(format!("{}.{}", name, i), value, row);
+            }
+        }
+        Value::Null => {}
+        value => {
+            row.insert(name, value);
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use std::{
+    path::Path,
+    process::{Command, Output, Stdio},
+};
+
+fn aoc(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .expect("Unable to run aoc")
+}
+
+/// A left out input is not read from stdin when it is `/dev/null`, as it is
+/// under CI or cron, and the day's own input is used instead.
+#[test]
+fn null_stdin_uses_input_of_day() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let year = aoc_solutions::WORKSPACE_YEAR.to_string();
+    let resolved = aoc(&[&year, "run", "1"]);
+
+    match aoc_solutions::input_path(root, aoc_solutions::WORKSPACE_YEAR, 1) {
+        Some(path) => {
+            let explicit = aoc(&[&year, "run", "1", path.to_str().unwrap()]);
+            assert!(resolved.status.success());
+            assert_eq!(resolved.stdout, explicit.stdout);
+        }
+        None => {
+            assert!(!resolved.status.success());
+            let stderr = String::from_utf8_lossy(&resolved.stderr);
+            assert!(stderr.contains("no input for day 1"), "{}", stderr);
+        }
+    }
+}
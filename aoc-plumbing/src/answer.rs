@@ -0,0 +1,219 @@
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
+
+use num::{BigInt, BigUint, ToPrimitive};
+use serde::{Serialize, Serializer};
+
+use crate::NoAnswer;
+
+/// The answer to a part of a problem.
+///
+/// Numeric answers compare by value regardless of how they are stored, so
+/// `Answer::Unsigned(5)`, `Answer::Signed(5)` and `Answer::Big(5.into())` are
+/// all equal, and they display and serialize identically. Integers that do not
+/// fit in 64 bits are serialized as strings, as most JSON consumers cannot
+/// represent them exactly.
+///
+/// # Usage
+///
+/// ```
+/// use aoc_plumbing::Answer;
+/// use num::BigUint;
+///
+/// let small = Answer::from(42_usize);
+/// let big = Answer::from(BigUint::from(42_u8));
+///
+/// assert_eq!(small, big);
+/// assert_eq!(small, 42);
+/// assert_eq!(Answer::from(-3_i64), Answer::Big((-3).into()));
+/// assert_ne!(Answer::from("42"), small);
+///
+/// let huge = Answer::from(BigUint::from(u64::MAX) * 2_u8);
+/// assert_eq!(huge.to_string(), "36893488147419103230");
+/// assert_eq!(serde_json::to_string(&huge).unwrap(), "\"36893488147419103230\"");
+/// assert_eq!(serde_json::to_string(&big).unwrap(), "42");
+/// assert_eq!(serde_json::to_string(&Answer::Missing).unwrap(), "null");
+///
+/// assert_eq!("36893488147419103230".parse::<Answer>().unwrap(), huge);
+/// assert_eq!("-7".parse::<Answer>().unwrap(), Answer::Signed(-7));
+/// assert_eq!("abc".parse::<Answer>().unwrap(), "abc");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub enum Answer {
+    Unsigned(u64),
+    Signed(i64),
+    Big(BigInt),
+    Text(String),
+    /// The problem does not have this part, see [NoAnswer].
+    #[default]
+    Missing,
+}
+
+impl Answer {
+    pub fn is_missing(&self) -> bool {
+        matches!(self, Self::Missing)
+    }
+
+    /// The value of a numeric answer as a [BigInt].
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        match self {
+            Self::Unsigned(v) => Some((*v).into()),
+            Self::Signed(v) => Some((*v).into()),
+            Self::Big(v) => Some(v.clone()),
+            Self::Text(_) | Self::Missing => None,
+        }
+    }
+
+    /// Compares two numeric answers by value.
+    fn cmp_numeric(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Unsigned(a), Self::Unsigned(b)) => Some(a.cmp(b)),
+            (Self::Signed(a), Self::Signed(b)) => Some(a.cmp(b)),
+            (Self::Unsigned(a), Self::Signed(b)) => Some(i128::from(*a).cmp(&i128::from(*b))),
+            (Self::Signed(a), Self::Unsigned(b)) => Some(i128::from(*a).cmp(&i128::from(*b))),
+            (a, b) => Some(a.to_bigint()?.cmp(&b.to_bigint()?)),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Text(a), Self::Text(b)) => a == b,
+            (Self::Missing, Self::Missing) => true,
+            (a, b) => a.cmp_numeric(b) == Some(Ordering::Equal),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl PartialOrd for Answer {
+    /// Numeric answers are ordered by value. Other answers are only comparable
+    /// to answers of the same kind.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Text(a), Self::Text(b)) => Some(a.cmp(b)),
+            (Self::Missing, Self::Missing) => Some(Ordering::Equal),
+            (a, b) => a.cmp_numeric(b),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsigned(v) => v.fmt(f),
+            Self::Signed(v) => v.fmt(f),
+            Self::Big(v) => v.fmt(f),
+            Self::Text(v) => v.fmt(f),
+            Self::Missing => NoAnswer.fmt(f),
+        }
+    }
+}
+
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Unsigned(v) => serializer.serialize_u64(*v),
+            Self::Signed(v) => serializer.serialize_i64(*v),
+            Self::Big(v) => {
+                if let Some(v) = v.to_u64() {
+                    serializer.serialize_u64(v)
+                } else if let Some(v) = v.to_i64() {
+                    serializer.serialize_i64(v)
+                } else {
+                    serializer.collect_str(v)
+                }
+            }
+            Self::Text(v) => serializer.serialize_str(v),
+            Self::Missing => serializer.serialize_none(),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = std::convert::Infallible;
+
+    /// Parses integers into the narrowest numeric variant that fits, and
+    /// anything else into [Answer::Text].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(v) = s.parse::<u64>() {
+            Ok(Self::Unsigned(v))
+        } else if let Ok(v) = s.parse::<i64>() {
+            Ok(Self::Signed(v))
+        } else if let Ok(v) = s.parse::<BigInt>() {
+            Ok(Self::Big(v))
+        } else {
+            Ok(Self::Text(s.to_string()))
+        }
+    }
+}
+
+macro_rules! impl_from_int {
+    ($variant:ident as $target:ty: $($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Self::$variant(value as $target)
+                }
+            }
+
+            impl PartialEq<$t> for Answer {
+                fn eq(&self, other: &$t) -> bool {
+                    self.cmp_numeric(&Self::$variant(*other as $target)) == Some(Ordering::Equal)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(Unsigned as u64: u8, u16, u32, u64, usize);
+impl_from_int!(Signed as i64: i8, i16, i32, i64, isize);
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        u64::try_from(value).map_or_else(|_| Self::Big(value.into()), Self::Unsigned)
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(value: i128) -> Self {
+        i64::try_from(value).map_or_else(|_| Self::Big(value.into()), Self::Signed)
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Self {
+        Self::Big(value)
+    }
+}
+
+impl From<BigUint> for Answer {
+    fn from(value: BigUint) -> Self {
+        Self::Big(value.into())
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<NoAnswer> for Answer {
+    fn from(_: NoAnswer) -> Self {
+        Self::Missing
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        matches!(self, Self::Text(v) if v == other)
+    }
+}
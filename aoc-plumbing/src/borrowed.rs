@@ -0,0 +1,196 @@
+use std::{fmt::Display, io::BufRead};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    Answer, Example, ImmutableParts, Normalize, Solution,
+    context::Timeout,
+    parallel,
+    report::{self, SolveReport, timed},
+    validate::Diagnostic,
+};
+
+/// A puzzle for a given day whose parsed form borrows from the input.
+///
+/// This is an alternative to [Problem](crate::Problem) for days where copying
+/// the input into owned data dominates the runtime. Instead of going through
+/// [FromStr](std::str::FromStr), the instance is built from a `&'a str` and is
+/// free to keep slices of it around.
+///
+/// Borrowed problems are registered by naming the type with an elided
+/// lifetime:
+///
+/// ```ignore
+/// aoc_plumbing::register_day!(SecretEntrance<'_>);
+/// ```
+///
+/// # Usage
+///
+/// ```
+/// use aoc_plumbing::{BorrowedProblem, Solution};
+///
+/// struct Words<'a>(Vec<&'a str>);
+///
+/// impl<'a> BorrowedProblem<'a> for Words<'a> {
+///     const YEAR: usize = 2025;
+///     const DAY: usize = 1;
+///     const TITLE: &'static str = "words";
+///     const README: &'static str = "";
+///
+///     type At<'b> = Words<'b>;
+///     type Config = ();
+///     type ProblemError = anyhow::Error;
+///     type P1 = usize;
+///     type P2 = String;
+///
+///     fn parse_with(raw_input: &'a str, _config: Self::Config) -> anyhow::Result<Self> {
+///         Ok(Self(raw_input.split_whitespace().collect()))
+///     }
+///
+///     fn part_one(&self) -> anyhow::Result<Self::P1> {
+///         Ok(self.0.len())
+///     }
+///
+///     fn part_two(&self) -> anyhow::Result<Self::P2> {
+///         Ok(self.0.iter().max_by_key(|w| w.len()).unwrap_or(&"").to_string())
+///     }
+/// }
+///
+/// let solution = Words::solve("the quick red fox").unwrap();
+/// assert_eq!(solution, Solution::new(4, "quick".to_string()));
+/// ```
+pub trait BorrowedProblem<'a>: Sized {
+    /// See [Problem::YEAR](crate::Problem::YEAR).
+    const YEAR: usize;
+    const DAY: usize;
+    const TITLE: &'static str;
+    const README: &'static str;
+    /// See [Problem::NORMALIZE](crate::Problem::NORMALIZE).
+    const NORMALIZE: Normalize = Normalize::ALL;
+
+    /// This problem for any other input lifetime, which is always the type
+    /// itself, as in `type At<'b> = Words<'b>`. The `solve` methods parse the
+    /// normalized copy of the input as this, as the copy does not live for
+    /// `'a`, and so the answers cannot borrow from the input.
+    type At<'b>: BorrowedProblem<
+            'b,
+            Config = Self::Config,
+            ProblemError = Self::ProblemError,
+            P1 = Self::P1,
+            P2 = Self::P2,
+        >;
+    /// See [Problem::Config](crate::Problem::Config).
+    type Config: Default + Serialize + DeserializeOwned;
+    /// See [Problem::ProblemError](crate::Problem::ProblemError).
+    type ProblemError: Into<anyhow::Error> + From<Timeout> + Send + Sync + 'static;
+    type P1: Display + Serialize + PartialEq + Into<Answer>;
+    type P2: Display + Serialize + PartialEq + Into<Answer>;
+
+    fn parse_with(raw_input: &'a str, config: Self::Config) -> Result<Self, Self::ProblemError>;
+
+    /// See [Problem::part_one](crate::Problem::part_one).
+    fn part_one(&self) -> Result<Self::P1, Self::ProblemError>;
+    /// See [Problem::part_two](crate::Problem::part_two).
+    fn part_two(&self) -> Result<Self::P2, Self::ProblemError>;
+
+    /// See [Problem::examples](crate::Problem::examples).
+    fn examples() -> Vec<Example> {
+        Vec::default()
+    }
+
+    /// See [Problem::extra](crate::Problem::extra).
+    fn extra(&self) -> Result<Vec<(String, Answer)>, Self::ProblemError> {
+        Ok(Vec::default())
+    }
+
+    /// See [Problem::validate](crate::Problem::validate).
+    fn validate(&self) -> Vec<Diagnostic> {
+        Vec::default()
+    }
+
+    /// See [Problem::from_reader](crate::Problem::from_reader).
+    fn from_reader(
+        _reader: impl BufRead,
+        _config: Self::Config,
+    ) -> Option<Result<Self, Self::ProblemError>> {
+        None
+    }
+
+    fn parse(raw_input: &'a str) -> Result<Self, Self::ProblemError> {
+        Self::parse_with(raw_input, Self::Config::default())
+    }
+
+    fn solve(raw_input: &'a str) -> Result<Solution<Self::P1, Self::P2>, Self::ProblemError> {
+        Self::solve_with(raw_input, Self::Config::default())
+    }
+
+    fn solve_with(
+        raw_input: &'a str,
+        config: Self::Config,
+    ) -> Result<Solution<Self::P1, Self::P2>, Self::ProblemError> {
+        Self::solve_timed_with(raw_input, config).map(|report| report.solution)
+    }
+
+    fn solve_timed(
+        raw_input: &'a str,
+    ) -> Result<SolveReport<Self::P1, Self::P2>, Self::ProblemError> {
+        Self::solve_timed_with(raw_input, Self::Config::default())
+    }
+
+    fn solve_timed_with(
+        raw_input: &'a str,
+        config: Self::Config,
+    ) -> Result<SolveReport<Self::P1, Self::P2>, Self::ProblemError> {
+        let (input, normalize) = report::normalized(Self::NORMALIZE, raw_input);
+        let (inst, parse) = timed(|| Self::At::parse_with(&input, config));
+        report::solve_timed(
+            (inst, normalize + parse),
+            Self::At::part_one,
+            Self::At::part_two,
+            Self::At::extra,
+        )
+    }
+
+    /// See [Problem::solve_parallel_timed_with](crate::Problem::solve_parallel_timed_with).
+    fn solve_parallel_timed_with(
+        raw_input: &'a str,
+        config: Self::Config,
+    ) -> Result<SolveReport<Self::P1, Self::P2>, Self::ProblemError>
+    where
+        for<'b> Self::At<'b>: ImmutableParts,
+        Self::P2: Send,
+    {
+        let (input, normalize) = report::normalized(Self::NORMALIZE, raw_input);
+        let (inst, parse) = timed(|| Self::At::parse_with(&input, config));
+        parallel::solve_timed(
+            (inst, normalize + parse),
+            Self::At::part_one,
+            Self::At::part_two,
+            Self::At::extra,
+        )
+    }
+
+    /// See [Problem::solve_reader](crate::Problem::solve_reader).
+    #[allow(clippy::type_complexity)]
+    fn solve_reader(
+        reader: impl BufRead,
+        config: Self::Config,
+    ) -> Option<Result<SolveReport<Self::P1, Self::P2>, Self::ProblemError>> {
+        let (inst, parse) = timed(|| Self::from_reader(reader, config));
+        Some(report::solve_timed(
+            (inst?, parse),
+            Self::part_one,
+            Self::part_two,
+            Self::extra,
+        ))
+    }
+}
+
+/// Names a [BorrowedProblem] for every input lifetime, which is what allows
+/// it to be [erased](crate::dynamic::erase_borrowed).
+///
+/// This is implemented by [register_day](crate::register_day), so it rarely
+/// needs to be implemented by hand.
+pub trait BorrowedFamily: 'static {
+    type Problem<'a>: BorrowedProblem<'a> + 'a;
+}
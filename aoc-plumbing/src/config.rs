@@ -0,0 +1,110 @@
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+/// Overrides for the fields of a problem's [Config](crate::Problem::Config),
+/// given as `key=value` pairs.
+///
+/// Values are interpreted according to the type of the field they replace:
+/// string fields take the value verbatim, and everything else is parsed as
+/// JSON.
+///
+/// # Usage
+///
+/// ```
+/// use aoc_plumbing::Params;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+/// struct Config {
+///     rounds: usize,
+///     start: String,
+/// }
+///
+/// let params = Params::from_iter([("rounds", "10"), ("start", "abc")]);
+/// let config = params.apply(Config::default()).unwrap();
+/// assert_eq!(config, Config { rounds: 10, start: "abc".into() });
+///
+/// let params = Params::default().with("colour", "red");
+/// assert!(params.apply(Config::default()).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params(Vec<(String, String)>);
+
+impl Params {
+    /// Adds an override, replacing any earlier override for the same key.
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    /// Adds an override, replacing any earlier override for the same key.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        let key = key.into();
+        self.0.retain(|(k, _)| *k != key);
+        self.0.push((key, value.into()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Parses a single `key=value` pair.
+    pub fn parse_pair(pair: &str) -> Result<(String, String)> {
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| anyhow!("expected a parameter of the form 'key=value', got '{pair}'"))?;
+        Ok((key.trim().to_string(), value.to_string()))
+    }
+
+    /// Returns `config` with the overridden fields replaced.
+    pub fn apply<C>(&self, config: C) -> Result<C>
+    where
+        C: Serialize + DeserializeOwned,
+    {
+        if self.is_empty() {
+            return Ok(config);
+        }
+
+        let mut value = serde_json::to_value(config)?;
+        let Value::Object(fields) = &mut value else {
+            bail!("this problem does not take any parameters");
+        };
+
+        for (key, raw) in self.iter() {
+            let Some(field) = fields.get_mut(key) else {
+                let known: Vec<_> = fields.keys().map(String::as_str).collect();
+                bail!(
+                    "unknown parameter '{}', expected one of: {}",
+                    key,
+                    known.join(", ")
+                );
+            };
+
+            *field = match field {
+                Value::String(_) => Value::String(raw.to_string()),
+                _ => serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string())),
+            };
+        }
+
+        serde_json::from_value(value).context("invalid parameter value")
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for Params
+where
+    K: Into<String>,
+    V: Into<String>,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut params = Self::default();
+        for (k, v) in iter {
+            params.insert(k, v);
+        }
+        params
+    }
+}
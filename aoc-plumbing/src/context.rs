@@ -0,0 +1,275 @@
+use std::{
+    cell::{Cell, RefCell},
+    error::Error,
+    fmt::Display,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU8, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// A phase of solving a problem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Phase {
+    Parse,
+    PartOne,
+    PartTwo,
+    /// Both parts at once, see [ImmutableParts](crate::ImmutableParts).
+    Parts,
+    Extra,
+}
+
+impl Phase {
+    /// A short name for the phase, for machine readable output.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Parse => "parse",
+            Self::PartOne => "part_one",
+            Self::PartTwo => "part_two",
+            Self::Parts => "parts",
+            Self::Extra => "extra",
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Parse,
+            1 => Self::PartOne,
+            2 => Self::PartTwo,
+            3 => Self::Parts,
+            _ => Self::Extra,
+        }
+    }
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse => write!(f, "parsing"),
+            Self::PartOne => write!(f, "solving part 1"),
+            Self::PartTwo => write!(f, "solving part 2"),
+            Self::Parts => write!(f, "solving both parts"),
+            Self::Extra => write!(f, "computing extra answers"),
+        }
+    }
+}
+
+/// The error produced when a solve runs past its deadline or is cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeout {
+    /// The phase that was running at the time.
+    pub phase: Phase,
+    /// The time limit that was exceeded, or `None` if the solve was
+    /// cancelled.
+    pub limit: Option<Duration>,
+}
+
+impl Display for Timeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.limit {
+            Some(limit) => write!(f, "timed out after {:?} while {}", limit, self.phase),
+            None => write!(f, "cancelled while {}", self.phase),
+        }
+    }
+}
+
+impl Error for Timeout {}
+
+#[derive(Debug)]
+struct Inner {
+    deadline: Option<(Instant, Duration)>,
+    cancelled: AtomicBool,
+    phase: AtomicU8,
+}
+
+/// Carries a deadline and cancellation flag for a solve.
+///
+/// A context is installed for the current thread via [SolveContext::run].
+/// The provided solve methods on [Problem](crate::Problem) check it between
+/// phases, and long running solvers should additionally poll [checkpoint] in
+/// their hot loops, propagating the [Timeout] it returns.
+///
+/// A context starts out in [Phase::Parse] and is meant for a single solve.
+/// Clones share the same state, so a context can be cancelled from another
+/// thread.
+///
+/// # Usage
+///
+/// ```
+/// use std::time::Duration;
+/// use aoc_plumbing::context::{Phase, SolveContext, checkpoint};
+///
+/// fn count_forever() -> Result<u64, aoc_plumbing::context::Timeout> {
+///     let mut count = 0_u64;
+///     loop {
+///         checkpoint()?;
+///         count = count.wrapping_add(1);
+///     }
+/// }
+///
+/// let ctx = SolveContext::with_timeout(Duration::from_millis(10));
+/// let err = ctx.run(count_forever).unwrap_err();
+/// assert_eq!(err.phase, Phase::Parse);
+/// assert_eq!(err.limit, Some(Duration::from_millis(10)));
+///
+/// // without a context, checkpoints never fail
+/// assert!(checkpoint().is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SolveContext {
+    inner: Arc<Inner>,
+}
+
+impl Default for SolveContext {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl SolveContext {
+    fn new(deadline: Option<(Instant, Duration)>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                deadline,
+                cancelled: AtomicBool::new(false),
+                phase: AtomicU8::new(Phase::Parse as u8),
+            }),
+        }
+    }
+
+    /// A context whose deadline is `timeout` from now.
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self::new(Some((Instant::now() + timeout, timeout)))
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn phase(&self) -> Phase {
+        Phase::from_u8(self.inner.phase.load(Ordering::Relaxed))
+    }
+
+    /// The error describing this context timing out in its current phase, or
+    /// in the part being solved on this thread while both parts are solved
+    /// at once.
+    pub fn timeout(&self) -> Timeout {
+        Timeout {
+            phase: PART.get().unwrap_or_else(|| self.phase()),
+            limit: match self.inner.cancelled.load(Ordering::Relaxed) {
+                true => None,
+                false => self.inner.deadline.map(|(_, limit)| limit),
+            },
+        }
+    }
+
+    /// Fails if the context has been cancelled or its deadline has passed.
+    pub fn check(&self) -> Result<(), Timeout> {
+        let expired = self
+            .inner
+            .deadline
+            .is_some_and(|(deadline, _)| Instant::now() >= deadline);
+
+        if expired || self.inner.cancelled.load(Ordering::Relaxed) {
+            Err(self.timeout())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Runs `f` with this as the current context for this thread.
+    pub fn run<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<SolveContext>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT.with_borrow_mut(|current| *current = self.0.take());
+            }
+        }
+
+        let _restore = Restore(CURRENT.replace(Some(self.clone())));
+        f()
+    }
+
+    /// The context installed for this thread, if any. Solvers that spread
+    /// work over other threads can use this to [run](SolveContext::run) the
+    /// work within the same context.
+    pub fn current() -> Option<Self> {
+        CURRENT.with_borrow(Clone::clone)
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<SolveContext>> = const { RefCell::new(None) };
+    static POLLS: Cell<u32> = const { Cell::new(0) };
+    /// The part solved on this thread while both parts are solved at once.
+    static PART: Cell<Option<Phase>> = const { Cell::new(None) };
+}
+
+/// How many calls to [checkpoint] are made per actual check of the clock.
+const POLL_INTERVAL: u32 = 64;
+
+/// Fails if the current context, if any, has been cancelled or has run past
+/// its deadline.
+///
+/// This is cheap enough to call on every iteration of a hot loop, as the
+/// context is only actually checked every few calls.
+pub fn checkpoint() -> Result<(), Timeout> {
+    let polls = POLLS.get().wrapping_add(1);
+    POLLS.set(polls);
+
+    if !polls.is_multiple_of(POLL_INTERVAL) {
+        return Ok(());
+    }
+
+    check()
+}
+
+/// Checks the current context, if any, and then moves it to `phase`. The
+/// check happens first so that a timeout is attributed to the phase that
+/// caused it.
+pub(crate) fn transition(phase: Phase) -> Result<(), Timeout> {
+    CURRENT.with_borrow(|current| match current {
+        Some(ctx) => {
+            ctx.check()?;
+            ctx.inner.phase.store(phase as u8, Ordering::Relaxed);
+            Ok(())
+        }
+        None => Ok(()),
+    })
+}
+
+/// Runs `f`, which solves `part` while the current context, if any, is in
+/// [Phase::Parts]. Timeouts on this thread are attributed to `part`, and once
+/// it is done the context moves on to the other part, which is then the only
+/// one still running.
+pub(crate) fn solve_part<R>(part: Phase, f: impl FnOnce() -> R) -> R {
+    let outer = PART.replace(Some(part));
+    let result = f();
+    PART.set(outer);
+
+    let other = match part {
+        Phase::PartOne => Phase::PartTwo,
+        _ => Phase::PartOne,
+    };
+    CURRENT.with_borrow(|current| {
+        if let Some(ctx) = current {
+            let _ = ctx.inner.phase.compare_exchange(
+                Phase::Parts as u8,
+                other as u8,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            );
+        }
+    });
+
+    result
+}
+
+/// Like [checkpoint], but always checks the current context. This is meant
+/// for calling before a long step that cannot poll for itself.
+pub fn check() -> Result<(), Timeout> {
+    CURRENT.with_borrow(|current| current.as_ref().map_or(Ok(()), SolveContext::check))
+}
@@ -0,0 +1,614 @@
+use std::{borrow::Cow, fmt::Display, io::BufRead, marker::PhantomData, time::Duration};
+
+use anyhow::{Context, Result};
+use self_cell::self_cell;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    Answer, BorrowedFamily, BorrowedProblem, Example, ImmutableParts, Normalize, Params, Problem,
+    Solution, SolveReport, Timings,
+    context::{self, Phase},
+    example::Part,
+    parallel,
+    problem::{long_description, problem_label},
+    report::{self, timed},
+    validate::Diagnostic,
+};
+
+/// A [Solution] whose answers have been erased into [Answer]s.
+pub type DynSolution = Solution<Answer, Answer>;
+
+/// A [SolveReport] whose answers have been erased into [Answer]s.
+pub type DynSolveReport = SolveReport<Answer, Answer>;
+
+/// An object-safe facade over [Problem].
+///
+/// [Problem] cannot be used as a trait object because of its associated
+/// consts and types. Every `Problem` can be erased into a `DynProblem` via
+/// [erase], which converts answers into [Answer]s and errors into
+/// [anyhow::Error]s. Likewise, [BorrowedProblem]s can be erased via
+/// [erase_borrowed].
+///
+/// # Usage
+///
+/// ```
+/// use std::str::FromStr;
+/// use aoc_plumbing::{Problem, dynamic::erase};
+///
+/// struct Echo(i64);
+///
+/// impl FromStr for Echo {
+///     type Err = anyhow::Error;
+///
+///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+///         Ok(Self(s.trim().parse()?))
+///     }
+/// }
+///
+/// impl Problem for Echo {
+///     const YEAR: usize = 2025;
+///     const DAY: usize = 1;
+///     const TITLE: &'static str = "echo";
+///     const README: &'static str = "";
+///
+///     type Config = ();
+///     type ProblemError = anyhow::Error;
+///     type P1 = i64;
+///     type P2 = i64;
+///
+///     fn part_one(&self) -> Result<Self::P1, Self::ProblemError> {
+///         Ok(self.0)
+///     }
+///
+///     fn part_two(&self) -> Result<Self::P2, Self::ProblemError> {
+///         Ok(-self.0)
+///     }
+/// }
+///
+/// let problem = erase::<Echo>();
+/// assert_eq!(problem.day(), 1);
+/// assert_eq!(problem.solve("5").unwrap().to_string(), "part 1: 5\npart 2: -5");
+/// ```
+pub trait DynProblem: Send + Sync {
+    fn year(&self) -> usize;
+    fn day(&self) -> usize;
+    fn title(&self) -> &'static str;
+    fn readme(&self) -> &'static str;
+    fn problem_label(&self) -> String;
+    fn long_description(&self) -> String;
+
+    /// See [Problem::examples].
+    fn examples(&self) -> Vec<Example>;
+
+    /// See [Problem::NORMALIZE].
+    fn normalize(&self) -> Normalize;
+
+    /// Parses the input, returning an instance whose parts can be solved
+    /// individually.
+    fn instance<'a>(&self, raw_input: &'a str) -> Result<Box<dyn DynInstance + 'a>> {
+        self.instance_with(raw_input, &Params::default())
+    }
+
+    fn solve(&self, raw_input: &str) -> Result<DynSolution> {
+        self.solve_with(raw_input, &Params::default())
+    }
+
+    fn solve_timed(&self, raw_input: &str) -> Result<DynSolveReport> {
+        self.solve_timed_with(raw_input, &Params::default())
+    }
+
+    fn solve_parallel(&self, raw_input: &str) -> Result<DynSolution> {
+        self.solve_parallel_timed_with(raw_input, &Params::default())
+            .map(|report| report.solution)
+    }
+
+    /// Like [DynProblem::instance], but with the given overrides applied to
+    /// the default [Config](Problem::Config).
+    ///
+    /// Like every method here that takes a raw input, this normalizes it
+    /// first according to [DynProblem::normalize].
+    fn instance_with<'a>(
+        &self,
+        raw_input: &'a str,
+        params: &Params,
+    ) -> Result<Box<dyn DynInstance + 'a>>;
+
+    /// Parses the input and checks it without solving it, see
+    /// [Problem::validate]. Inputs that do not parse are an error instead.
+    fn validate_with(&self, raw_input: &str, params: &Params) -> Result<Vec<Diagnostic>> {
+        Ok(self.instance_with(raw_input, params)?.validate())
+    }
+
+    fn solve_with(&self, raw_input: &str, params: &Params) -> Result<DynSolution>;
+    fn solve_timed_with(&self, raw_input: &str, params: &Params) -> Result<DynSolveReport>;
+
+    /// Like [DynProblem::solve_timed_with], but solves both parts at the same
+    /// time if the problem was erased via [erase_parallel] or
+    /// [erase_borrowed_parallel]. Other problems are solved as usual.
+    fn solve_parallel_timed_with(&self, raw_input: &str, params: &Params)
+    -> Result<DynSolveReport>;
+
+    /// Like [DynProblem::solve_timed_with], but only solves `part`, so that
+    /// no time is spent on the other one. The other part is left as
+    /// [Answer::Missing], and extra answers are not computed.
+    fn solve_part_timed_with(
+        &self,
+        raw_input: &str,
+        params: &Params,
+        part: Part,
+    ) -> Result<DynSolveReport> {
+        let (inst, parse) = timed(|| self.instance_with(raw_input, params));
+        let inst = inst?;
+        let mut report = DynSolveReport {
+            solution: DynSolution::new(Answer::Missing, Answer::Missing),
+            timings: Timings {
+                parse,
+                ..Timings::default()
+            },
+        };
+
+        match part {
+            Part::One => {
+                context::transition(Phase::PartOne)?;
+                let (answer, time) = timed(|| inst.part_one());
+                report.solution.part_one = answer?;
+                report.timings.part_one = time;
+            }
+            Part::Two => {
+                context::transition(Phase::PartTwo)?;
+                let (answer, time) = timed(|| inst.part_two());
+                report.solution.part_two = answer?;
+                report.timings.part_two = time;
+            }
+        }
+        context::check()?;
+
+        Ok(report)
+    }
+
+    /// Solves the problem by reading the input incrementally, see
+    /// [Problem::from_reader]. Returns `None`, without reading anything, if
+    /// the problem does not support this.
+    fn solve_reader(
+        &self,
+        reader: &mut dyn BufRead,
+        params: &Params,
+    ) -> Option<Result<DynSolveReport>>;
+}
+
+/// An object-safe facade over a parsed [Problem] or [BorrowedProblem]
+/// instance. As with the problems themselves, parts can be solved in any
+/// order, any number of times.
+pub trait DynInstance {
+    fn part_one(&self) -> Result<Answer>;
+    fn part_two(&self) -> Result<Answer>;
+    fn extra(&self) -> Result<Vec<(String, Answer)>>;
+
+    /// See [Problem::validate].
+    fn validate(&self) -> Vec<Diagnostic>;
+}
+
+/// Erases `P` into a boxed [DynProblem].
+pub fn erase<P>() -> Box<dyn DynProblem>
+where
+    P: Problem + 'static,
+    <P as Problem>::ProblemError: Into<anyhow::Error>,
+{
+    Box::new(Erased::<P, Serial>(PhantomData))
+}
+
+/// Like [erase], but for a problem whose parts can be solved at the same
+/// time, see [DynProblem::solve_parallel_timed_with].
+pub fn erase_parallel<P>() -> Box<dyn DynProblem>
+where
+    P: Problem + ImmutableParts + 'static,
+    <P as Problem>::ProblemError: Into<anyhow::Error>,
+    <P as Problem>::P2: Send,
+{
+    Box::new(Erased::<P, Parallel>(PhantomData))
+}
+
+/// Solves the parts of an erased problem one after the other.
+enum Serial {}
+
+/// Solves the parts of an erased problem at the same time.
+enum Parallel {}
+
+/// How an erased [Problem] `P` solves its parts.
+trait Parts<P: Problem> {
+    fn solve_timed_with(
+        raw_input: &str,
+        config: P::Config,
+    ) -> Result<SolveReport<P::P1, P::P2>, P::ProblemError>;
+}
+
+impl<P: Problem> Parts<P> for Serial {
+    fn solve_timed_with(
+        raw_input: &str,
+        config: P::Config,
+    ) -> Result<SolveReport<P::P1, P::P2>, P::ProblemError> {
+        P::solve_timed_with(raw_input, config)
+    }
+}
+
+impl<P> Parts<P> for Parallel
+where
+    P: Problem + ImmutableParts,
+    P::P2: Send,
+{
+    fn solve_timed_with(
+        raw_input: &str,
+        config: P::Config,
+    ) -> Result<SolveReport<P::P1, P::P2>, P::ProblemError> {
+        P::solve_parallel_timed_with(raw_input, config)
+    }
+}
+
+struct Erased<P, M>(PhantomData<fn() -> (P, M)>);
+
+impl<P, M> DynProblem for Erased<P, M>
+where
+    P: Problem + 'static,
+    <P as Problem>::ProblemError: Into<anyhow::Error>,
+    M: Parts<P> + 'static,
+{
+    fn year(&self) -> usize {
+        P::YEAR
+    }
+
+    fn day(&self) -> usize {
+        P::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        P::TITLE
+    }
+
+    fn readme(&self) -> &'static str {
+        P::README
+    }
+
+    fn problem_label(&self) -> String {
+        P::problem_label()
+    }
+
+    fn long_description(&self) -> String {
+        P::long_description()
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        P::examples()
+    }
+
+    fn normalize(&self) -> Normalize {
+        P::NORMALIZE
+    }
+
+    fn instance_with<'a>(
+        &self,
+        raw_input: &'a str,
+        params: &Params,
+    ) -> Result<Box<dyn DynInstance + 'a>> {
+        let input = P::NORMALIZE.apply(raw_input);
+        let inst = P::instance_with(&input, config(params, &P::problem_label())?)
+            .map_err(Into::<P::ProblemError>::into)
+            .map_err(Into::into)?;
+        Ok(Box::new(inst))
+    }
+
+    fn solve_with(&self, raw_input: &str, params: &Params) -> Result<DynSolution> {
+        let solution =
+            P::solve_with(raw_input, config(params, &P::problem_label())?).map_err(Into::into)?;
+        Ok(erase_solution(solution))
+    }
+
+    fn solve_timed_with(&self, raw_input: &str, params: &Params) -> Result<DynSolveReport> {
+        let report = P::solve_timed_with(raw_input, config(params, &P::problem_label())?)
+            .map_err(Into::into)?;
+        Ok(erase_report(report))
+    }
+
+    fn solve_parallel_timed_with(
+        &self,
+        raw_input: &str,
+        params: &Params,
+    ) -> Result<DynSolveReport> {
+        let report = M::solve_timed_with(raw_input, config(params, &P::problem_label())?)
+            .map_err(Into::into)?;
+        Ok(erase_report(report))
+    }
+
+    fn solve_reader(
+        &self,
+        reader: &mut dyn BufRead,
+        params: &Params,
+    ) -> Option<Result<DynSolveReport>> {
+        let config = match config(params, &P::problem_label()) {
+            Ok(config) => config,
+            Err(e) => return Some(Err(e)),
+        };
+        let report = P::solve_reader(reader, config)?.map_err(Into::into);
+        Some(report.map(erase_report))
+    }
+}
+
+impl<P> DynInstance for P
+where
+    P: Problem,
+    <P as Problem>::ProblemError: Into<anyhow::Error>,
+{
+    fn part_one(&self) -> Result<Answer> {
+        Ok(Problem::part_one(self).map_err(Into::into)?.into())
+    }
+
+    fn part_two(&self) -> Result<Answer> {
+        Ok(Problem::part_two(self).map_err(Into::into)?.into())
+    }
+
+    fn extra(&self) -> Result<Vec<(String, Answer)>> {
+        Problem::extra(self).map_err(Into::into)
+    }
+
+    fn validate(&self) -> Vec<Diagnostic> {
+        Problem::validate(self)
+    }
+}
+
+/// Erases the [BorrowedProblem] named by `F` into a boxed [DynProblem].
+pub fn erase_borrowed<F: BorrowedFamily>() -> Box<dyn DynProblem> {
+    Box::new(ErasedBorrowed::<F, Serial>(PhantomData))
+}
+
+/// Like [erase_borrowed], but for a problem whose parts can be solved at the
+/// same time, see [DynProblem::solve_parallel_timed_with].
+pub fn erase_borrowed_parallel<F>() -> Box<dyn DynProblem>
+where
+    F: BorrowedFamily,
+    for<'a> F::Problem<'a>: ImmutableParts,
+    for<'a> <F::Problem<'a> as BorrowedProblem<'a>>::P2: Send,
+{
+    Box::new(ErasedBorrowed::<F, Parallel>(PhantomData))
+}
+
+/// The parts of the [BorrowedProblem] named by a family, see [Parts].
+type Member<'a, F> = <F as BorrowedFamily>::Problem<'a>;
+type BorrowedReport<'a, F> = SolveReport<
+    <Member<'a, F> as BorrowedProblem<'a>>::P1,
+    <Member<'a, F> as BorrowedProblem<'a>>::P2,
+>;
+
+type BorrowedError<'a, F> = <Member<'a, F> as BorrowedProblem<'a>>::ProblemError;
+
+/// How an erased [BorrowedProblem] solves its parts, see [Parts]. Unlike
+/// there, the parse is done by the erased problem, as only it can keep the
+/// normalized copy of the input alive for the parts.
+trait BorrowedParts<F: BorrowedFamily> {
+    fn solve_timed<'a>(
+        parsed: (Result<Member<'a, F>, BorrowedError<'a, F>>, Duration),
+    ) -> Result<BorrowedReport<'a, F>, BorrowedError<'a, F>>;
+}
+
+impl<F: BorrowedFamily> BorrowedParts<F> for Serial {
+    fn solve_timed<'a>(
+        parsed: (Result<Member<'a, F>, BorrowedError<'a, F>>, Duration),
+    ) -> Result<BorrowedReport<'a, F>, BorrowedError<'a, F>> {
+        report::solve_timed(
+            parsed,
+            Member::<F>::part_one,
+            Member::<F>::part_two,
+            Member::<F>::extra,
+        )
+    }
+}
+
+impl<F> BorrowedParts<F> for Parallel
+where
+    F: BorrowedFamily,
+    for<'a> F::Problem<'a>: ImmutableParts,
+    for<'a> <F::Problem<'a> as BorrowedProblem<'a>>::P2: Send,
+{
+    fn solve_timed<'a>(
+        parsed: (Result<Member<'a, F>, BorrowedError<'a, F>>, Duration),
+    ) -> Result<BorrowedReport<'a, F>, BorrowedError<'a, F>> {
+        parallel::solve_timed(
+            parsed,
+            Member::<F>::part_one,
+            Member::<F>::part_two,
+            Member::<F>::extra,
+        )
+    }
+}
+
+struct ErasedBorrowed<F, M>(PhantomData<fn() -> (F, M)>);
+
+impl<F: BorrowedFamily, M: BorrowedParts<F> + 'static> ErasedBorrowed<F, M> {
+    /// Normalizes the input and parses it, timing both together as owned
+    /// problems do, then solves the parts as `S` does.
+    fn solve_timed<S: BorrowedParts<F>>(
+        &self,
+        raw_input: &str,
+        params: &Params,
+    ) -> Result<DynSolveReport> {
+        let (input, normalize) = report::normalized(self.normalize(), raw_input);
+        let config = config(params, &self.problem_label())?;
+        let (inst, parse) = timed(|| F::Problem::parse_with(&input, config));
+        let report = S::solve_timed((inst, normalize + parse)).map_err(Into::into)?;
+        Ok(erase_report(report))
+    }
+}
+
+/// The metadata of a borrowed problem does not depend on the lifetime.
+type Static<F> = <F as BorrowedFamily>::Problem<'static>;
+
+impl<F: BorrowedFamily, M: BorrowedParts<F> + 'static> DynProblem for ErasedBorrowed<F, M> {
+    fn year(&self) -> usize {
+        Static::<F>::YEAR
+    }
+
+    fn day(&self) -> usize {
+        Static::<F>::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        Static::<F>::TITLE
+    }
+
+    fn readme(&self) -> &'static str {
+        Static::<F>::README
+    }
+
+    fn problem_label(&self) -> String {
+        problem_label(self.day(), self.title())
+    }
+
+    fn long_description(&self) -> String {
+        long_description(self.day(), self.readme())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        Static::<F>::examples()
+    }
+
+    fn normalize(&self) -> Normalize {
+        Static::<F>::NORMALIZE
+    }
+
+    fn instance_with<'a>(
+        &self,
+        raw_input: &'a str,
+        params: &Params,
+    ) -> Result<Box<dyn DynInstance + 'a>> {
+        let label = self.problem_label();
+
+        match self.normalize().apply(raw_input) {
+            Cow::Borrowed(input) => {
+                let inst = F::Problem::<'a>::parse_with(input, config(params, &label)?)
+                    .map_err(Into::into)?;
+                Ok(Box::new(Borrowed(inst)))
+            }
+            Cow::Owned(input) => {
+                let inst = Normalized::try_new(input, |input| {
+                    let inst = F::Problem::parse_with(input, config(params, &label)?)
+                        .map_err(Into::into)?;
+                    Ok::<BoxedInstance, anyhow::Error>(Box::new(Borrowed(inst)))
+                })?;
+                Ok(Box::new(inst))
+            }
+        }
+    }
+
+    fn solve_with(&self, raw_input: &str, params: &Params) -> Result<DynSolution> {
+        self.solve_timed_with(raw_input, params)
+            .map(|report| report.solution)
+    }
+
+    fn solve_timed_with(&self, raw_input: &str, params: &Params) -> Result<DynSolveReport> {
+        self.solve_timed::<Serial>(raw_input, params)
+    }
+
+    fn solve_parallel_timed_with(
+        &self,
+        raw_input: &str,
+        params: &Params,
+    ) -> Result<DynSolveReport> {
+        self.solve_timed::<M>(raw_input, params)
+    }
+
+    fn solve_reader(
+        &self,
+        reader: &mut dyn BufRead,
+        params: &Params,
+    ) -> Option<Result<DynSolveReport>> {
+        let config = match config(params, &self.problem_label()) {
+            Ok(config) => config,
+            Err(e) => return Some(Err(e)),
+        };
+        let report = Static::<F>::solve_reader(reader, config)?.map_err(Into::into);
+        Some(report.map(erase_report))
+    }
+}
+
+/// [DynInstance] cannot be implemented for every [BorrowedProblem] directly,
+/// as that would overlap with the implementation for every [Problem].
+struct Borrowed<P>(P);
+
+impl<'a, P: BorrowedProblem<'a>> DynInstance for Borrowed<P> {
+    fn part_one(&self) -> Result<Answer> {
+        Ok(self.0.part_one().map_err(Into::into)?.into())
+    }
+
+    fn part_two(&self) -> Result<Answer> {
+        Ok(self.0.part_two().map_err(Into::into)?.into())
+    }
+
+    fn extra(&self) -> Result<Vec<(String, Answer)>> {
+        self.0.extra().map_err(Into::into)
+    }
+
+    fn validate(&self) -> Vec<Diagnostic> {
+        self.0.validate()
+    }
+}
+
+type BoxedInstance<'a> = Box<dyn DynInstance + 'a>;
+
+self_cell!(
+    /// A borrowed instance along with the normalized copy of the input that
+    /// it borrows from.
+    struct Normalized {
+        owner: String,
+
+        #[covariant]
+        dependent: BoxedInstance,
+    }
+);
+
+impl DynInstance for Normalized {
+    fn part_one(&self) -> Result<Answer> {
+        self.with_dependent(|_, inst| inst.part_one())
+    }
+
+    fn part_two(&self) -> Result<Answer> {
+        self.with_dependent(|_, inst| inst.part_two())
+    }
+
+    fn extra(&self) -> Result<Vec<(String, Answer)>> {
+        self.with_dependent(|_, inst| inst.extra())
+    }
+
+    fn validate(&self) -> Vec<Diagnostic> {
+        self.with_dependent(|_, inst| inst.validate())
+    }
+}
+
+fn config<C>(params: &Params, label: &str) -> Result<C>
+where
+    C: Default + Serialize + DeserializeOwned,
+{
+    params
+        .apply(C::default())
+        .with_context(|| format!("Invalid parameters for {}", label))
+}
+
+fn erase_solution<T, G>(solution: Solution<T, G>) -> DynSolution
+where
+    T: Display + Serialize + PartialEq + Into<Answer>,
+    G: Display + Serialize + PartialEq + Into<Answer>,
+{
+    DynSolution {
+        part_one: solution.part_one.into(),
+        part_two: solution.part_two.into(),
+        extra: solution.extra,
+    }
+}
+
+fn erase_report<T, G>(report: SolveReport<T, G>) -> DynSolveReport
+where
+    T: Display + Serialize + PartialEq + Into<Answer>,
+    G: Display + Serialize + PartialEq + Into<Answer>,
+{
+    SolveReport {
+        solution: erase_solution(report.solution),
+        timings: report.timings,
+    }
+}
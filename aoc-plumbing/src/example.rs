@@ -0,0 +1,261 @@
+use std::{borrow::Cow, fmt::Display, path::Path};
+
+use anyhow::{Context, Result, bail};
+
+use crate::{Answer, DynProblem, Params};
+
+/// One of the two parts of a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::One => write!(f, "part 1"),
+            Self::Two => write!(f, "part 2"),
+        }
+    }
+}
+
+/// Where the input of an [Example] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExampleInput {
+    /// A file, relative to the day's crate.
+    File(&'static str),
+    /// The input itself.
+    Text(&'static str),
+}
+
+/// An example input from the puzzle description, along with the answers it
+/// is expected to produce.
+///
+/// Examples are declared by [Problem::examples](crate::Problem::examples) and
+/// turned into unit tests by [example_tests](crate::example_tests). Only the
+/// parts with an expected answer are checked, so an example that only
+/// applies to one part simply leaves the other out.
+///
+/// # Usage
+///
+/// ```
+/// use aoc_plumbing::{Example, dynamic::erase};
+/// # use std::str::FromStr;
+/// # use aoc_plumbing::Problem;
+/// # struct Echo(i64);
+/// # impl FromStr for Echo {
+/// #     type Err = anyhow::Error;
+/// #     fn from_str(s: &str) -> Result<Self, Self::Err> { Ok(Self(s.trim().parse()?)) }
+/// # }
+/// # impl Problem for Echo {
+/// #     const YEAR: usize = 2025;
+/// #     const DAY: usize = 3;
+/// #     const TITLE: &'static str = "echo";
+/// #     const README: &'static str = "";
+/// #     type Config = ();
+/// #     type ProblemError = anyhow::Error;
+/// #     type P1 = i64;
+/// #     type P2 = i64;
+/// #     fn part_one(&self) -> Result<Self::P1, Self::ProblemError> { Ok(self.0) }
+/// #     fn part_two(&self) -> Result<Self::P2, Self::ProblemError> { Ok(-self.0) }
+/// # }
+///
+/// let problem = erase::<Echo>();
+///
+/// let example = Example::text("5").part_one(5).part_two(-5);
+/// assert!(example.check(&*problem, ".").is_ok());
+///
+/// let example = Example::text("5").part_two(5);
+/// let err = example.check(&*problem, ".").unwrap_err();
+/// assert_eq!(err.to_string(), "inline example: part 2 was -5, expected 5");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Example {
+    pub input: ExampleInput,
+    pub params: Params,
+    pub part_one: Option<Answer>,
+    pub part_two: Option<Answer>,
+}
+
+impl Example {
+    fn new(input: ExampleInput) -> Self {
+        Self {
+            input,
+            params: Params::default(),
+            part_one: None,
+            part_two: None,
+        }
+    }
+
+    /// An example read from `path`, relative to the day's crate.
+    pub fn file(path: &'static str) -> Self {
+        Self::new(ExampleInput::File(path))
+    }
+
+    /// An example whose input is given inline.
+    pub fn text(input: &'static str) -> Self {
+        Self::new(ExampleInput::Text(input))
+    }
+
+    /// Overrides a puzzle parameter for this example, see [Params].
+    pub fn param(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.params.insert(key, value.to_string());
+        self
+    }
+
+    pub fn part_one(mut self, expected: impl Into<Answer>) -> Self {
+        self.part_one = Some(expected.into());
+        self
+    }
+
+    pub fn part_two(mut self, expected: impl Into<Answer>) -> Self {
+        self.part_two = Some(expected.into());
+        self
+    }
+
+    pub fn expected(&self, part: Part) -> Option<&Answer> {
+        match part {
+            Part::One => self.part_one.as_ref(),
+            Part::Two => self.part_two.as_ref(),
+        }
+    }
+
+    /// Reads the input, resolving files relative to `dir`.
+    pub fn read(&self, dir: impl AsRef<Path>) -> Result<Cow<'static, str>> {
+        match self.input {
+            ExampleInput::File(path) => std::fs::read_to_string(dir.as_ref().join(path))
+                .map(Cow::Owned)
+                .with_context(|| format!("Unable to load {}", path)),
+            ExampleInput::Text(input) => Ok(Cow::Borrowed(input)),
+        }
+    }
+
+    /// Solves every part with an expected answer, failing on the first that
+    /// does not match.
+    pub fn check(&self, problem: &dyn DynProblem, dir: impl AsRef<Path>) -> Result<()> {
+        self.check_parts(problem, dir.as_ref(), &[Part::One, Part::Two])
+    }
+
+    fn check_parts(&self, problem: &dyn DynProblem, dir: &Path, parts: &[Part]) -> Result<()> {
+        let input = self.read(dir)?;
+        let inst = problem
+            .instance_with(&input, &self.params)
+            .with_context(|| format!("{}: failed to parse", self))?;
+
+        for &part in parts {
+            let Some(expected) = self.expected(part) else {
+                continue;
+            };
+
+            let actual = match part {
+                Part::One => inst.part_one(),
+                Part::Two => inst.part_two(),
+            }
+            .with_context(|| format!("{}: {} failed", self, part))?;
+
+            if actual != *expected {
+                bail!("{}: {} was {}, expected {}", self, part, actual, expected);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Example {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.input {
+            ExampleInput::File(path) => write!(f, "{}", path),
+            ExampleInput::Text(_) => write!(f, "inline example"),
+        }
+    }
+}
+
+/// Checks `part` against every example of `problem` that has an expected
+/// answer for it, reporting all of the mismatches together.
+pub fn check_examples(problem: &dyn DynProblem, dir: impl AsRef<Path>, part: Part) -> Result<()> {
+    let failures: Vec<_> = problem
+        .examples()
+        .iter()
+        .filter(|example| example.expected(part).is_some())
+        .filter_map(|example| example.check_parts(problem, dir.as_ref(), &[part]).err())
+        .map(|e| format!("{:#}", e))
+        .collect();
+
+    if !failures.is_empty() {
+        bail!("{}", failures.join("\n"));
+    }
+
+    Ok(())
+}
+
+/// Generates unit tests for the [examples](crate::Problem::examples) of a
+/// problem, one per part.
+///
+/// The problem is named as for [register_day](crate::register_day). The
+/// answers for the real input, `input.txt`, may also be given, which
+/// generates an ignored `full_dataset` test:
+///
+/// ```ignore
+/// aoc_plumbing::example_tests!(Reactor, full_dataset = (670, 332052564714990));
+/// ```
+#[macro_export]
+macro_rules! example_tests {
+    (@tests $erase:block $(, ($p1:expr, $p2:expr))?) => {
+        #[cfg(test)]
+        mod example_tests {
+            use super::*;
+
+            fn problem() -> ::std::boxed::Box<dyn $crate::DynProblem> $erase
+
+            #[test]
+            fn example_part_one() {
+                $crate::example::check_examples(
+                    &*problem(),
+                    env!("CARGO_MANIFEST_DIR"),
+                    $crate::example::Part::One,
+                )
+                .unwrap();
+            }
+
+            #[test]
+            fn example_part_two() {
+                $crate::example::check_examples(
+                    &*problem(),
+                    env!("CARGO_MANIFEST_DIR"),
+                    $crate::example::Part::Two,
+                )
+                .unwrap();
+            }
+
+            $(
+                #[test]
+                #[ignore]
+                fn full_dataset() {
+                    $crate::Example::file("input.txt")
+                        .part_one($p1)
+                        .part_two($p2)
+                        .check(&*problem(), env!("CARGO_MANIFEST_DIR"))
+                        .unwrap();
+                }
+            )?
+        }
+    };
+    ($problem:ident<'_> $(, full_dataset = ($p1:expr, $p2:expr))? $(,)?) => {
+        $crate::example_tests!(@tests {
+            struct Family;
+
+            impl $crate::BorrowedFamily for Family {
+                type Problem<'a> = $problem<'a>;
+            }
+
+            $crate::dynamic::erase_borrowed::<Family>()
+        } $(, ($p1, $p2))?);
+    };
+    ($problem:ty $(, full_dataset = ($p1:expr, $p2:expr))? $(,)?) => {
+        $crate::example_tests!(@tests {
+            $crate::dynamic::erase::<$problem>()
+        } $(, ($p1, $p2))?);
+    };
+}
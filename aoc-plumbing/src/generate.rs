@@ -0,0 +1,106 @@
+use std::marker::PhantomData;
+
+use anyhow::{Context, Result};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::Params;
+
+/// Produces random inputs for a problem that are structurally valid, i.e.
+/// that satisfy the same constraints as the real input.
+///
+/// Generators are registered alongside the problem via
+/// [register_generator](crate::register_generator), and can then be run from
+/// the cli with `aoc generate`.
+///
+/// # Usage
+///
+/// ```
+/// use aoc_plumbing::generate::Generate;
+/// use rand::Rng;
+/// use serde::{Deserialize, Serialize};
+///
+/// struct Sonar;
+///
+/// #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+/// struct SonarSize {
+///     depths: usize,
+/// }
+///
+/// impl Default for SonarSize {
+///     fn default() -> Self {
+///         Self { depths: 2000 }
+///     }
+/// }
+///
+/// impl Generate for Sonar {
+///     type Size = SonarSize;
+///
+///     fn generate(rng: &mut impl Rng, size: &Self::Size) -> String {
+///         (0..size.depths)
+///             .map(|_| rng.random_range(100..10000).to_string())
+///             .collect::<Vec<_>>()
+///             .join("\n")
+///     }
+/// }
+///
+/// let input = Sonar::generate_seeded(7, &SonarSize { depths: 3 });
+/// assert_eq!(input.lines().count(), 3);
+/// assert_eq!(input, Sonar::generate_seeded(7, &SonarSize { depths: 3 }));
+/// ```
+pub trait Generate {
+    /// Knobs controlling the size and shape of the generated input. The
+    /// default should produce inputs comparable to the real one. Fields can be
+    /// overridden by name at runtime via [Params].
+    type Size: Default + Serialize + DeserializeOwned;
+
+    fn generate(rng: &mut impl Rng, size: &Self::Size) -> String;
+
+    /// Generates an input deterministically from `seed`.
+    fn generate_seeded(seed: u64, size: &Self::Size) -> String {
+        Self::generate(&mut StdRng::seed_from_u64(seed), size)
+    }
+}
+
+/// An object-safe facade over [Generate], keyed by the year and day of the
+/// problem it generates inputs for.
+pub trait DynGenerate: Send + Sync {
+    fn year(&self) -> usize;
+    fn day(&self) -> usize;
+
+    /// Generates an input from `seed`, with the given overrides applied to
+    /// the default [Size](Generate::Size).
+    fn generate(&self, seed: u64, params: &Params) -> Result<String>;
+}
+
+/// Erases `G` into a boxed [DynGenerate] for the given year and day.
+pub fn erase_generator<G: Generate + 'static>(year: usize, day: usize) -> Box<dyn DynGenerate> {
+    Box::new(ErasedGenerator::<G> {
+        year,
+        day,
+        generator: PhantomData,
+    })
+}
+
+struct ErasedGenerator<G> {
+    year: usize,
+    day: usize,
+    generator: PhantomData<fn() -> G>,
+}
+
+impl<G: Generate + 'static> DynGenerate for ErasedGenerator<G> {
+    fn year(&self) -> usize {
+        self.year
+    }
+
+    fn day(&self) -> usize {
+        self.day
+    }
+
+    fn generate(&self, seed: u64, params: &Params) -> Result<String> {
+        let size = params
+            .apply(G::Size::default())
+            .with_context(|| format!("Invalid size for day {}", self.day))?;
+        Ok(G::generate_seeded(seed, &size))
+    }
+}
@@ -1,3 +1,33 @@
+pub mod answer;
+pub mod borrowed;
+pub mod config;
+pub mod context;
+pub mod dynamic;
+pub mod example;
+pub mod generate;
+pub mod normalize;
+pub mod parallel;
+pub mod parse;
 pub mod problem;
+pub mod reference;
+pub mod registry;
+pub mod report;
+pub mod trace;
+pub mod validate;
+pub mod visualize;
 
-pub use problem::{Problem, Solution};
+#[doc(hidden)]
+pub use linkme;
+
+pub use answer::Answer;
+pub use borrowed::{BorrowedFamily, BorrowedProblem};
+pub use config::Params;
+pub use context::{SolveContext, Timeout};
+pub use dynamic::{DynInstance, DynProblem, DynSolution};
+pub use example::Example;
+pub use normalize::Normalize;
+pub use parallel::ImmutableParts;
+pub use parse::ParseError;
+pub use problem::{NoAnswer, Problem, Solution};
+pub use registry::Registry;
+pub use report::{SolveReport, Timings};
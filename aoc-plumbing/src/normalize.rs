@@ -0,0 +1,115 @@
+use std::borrow::Cow;
+
+/// The cleanups applied to raw inputs before they are parsed, so that inputs
+/// saved by different editors and platforms parse the same.
+///
+/// Each problem declares the normalizations it can receive via
+/// [Problem::NORMALIZE](crate::Problem::NORMALIZE), which defaults to
+/// [Normalize::ALL]. A problem whose input has meaningful whitespace can opt
+/// out of the normalizations that would change it.
+///
+/// # Usage
+///
+/// ```
+/// use aoc_plumbing::Normalize;
+///
+/// let input = "\u{feff}1 2  \r\n3 4\r\n\r\n";
+///
+/// assert_eq!(Normalize::ALL.apply(input), "1 2\n3 4");
+/// assert_eq!(Normalize::NONE.apply(input), input);
+///
+/// let keep_whitespace = Normalize {
+///     trailing_whitespace: false,
+///     ..Normalize::ALL
+/// };
+/// assert_eq!(keep_whitespace.apply(input), "1 2  \n3 4");
+///
+/// // nothing is copied if there is nothing to change
+/// assert!(matches!(Normalize::ALL.apply("1 2\n3 4\n"), std::borrow::Cow::Borrowed(_)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Normalize {
+    /// Removes a leading UTF-8 byte order mark.
+    pub bom: bool,
+    /// Converts CRLF line endings to LF.
+    pub crlf: bool,
+    /// Removes spaces and tabs from the end of every line. This only applies
+    /// to lines ending in CRLF if they are also converted.
+    pub trailing_whitespace: bool,
+    /// Removes blank lines from the end of the input, along with the final
+    /// line ending.
+    pub trailing_blank_lines: bool,
+}
+
+impl Default for Normalize {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl Normalize {
+    pub const ALL: Self = Self {
+        bom: true,
+        crlf: true,
+        trailing_whitespace: true,
+        trailing_blank_lines: true,
+    };
+
+    pub const NONE: Self = Self {
+        bom: false,
+        crlf: false,
+        trailing_whitespace: false,
+        trailing_blank_lines: false,
+    };
+
+    /// Applies the enabled normalizations to `input`, only copying it if
+    /// something needs to change in the middle of it.
+    pub fn apply<'a>(&self, input: &'a str) -> Cow<'a, str> {
+        let mut input = input;
+
+        if self.bom {
+            input = input.strip_prefix('\u{feff}').unwrap_or(input);
+        }
+
+        if self.trailing_blank_lines {
+            input = trim_blank_lines(input);
+        }
+
+        let mut ret = Cow::Borrowed(input);
+
+        if self.crlf && ret.contains("\r\n") {
+            ret = Cow::Owned(ret.replace("\r\n", "\n"));
+        }
+
+        if self.trailing_whitespace && ret.split('\n').any(|l| trim_line(l).len() != l.len()) {
+            ret = Cow::Owned(
+                ret.split('\n')
+                    .map(trim_line)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+        }
+
+        ret
+    }
+}
+
+/// Keeps everything up to the end of the last line with any content, which
+/// is left as is.
+fn trim_blank_lines(input: &str) -> &str {
+    let Some(last) = input.rfind(|c: char| !c.is_whitespace()) else {
+        return "";
+    };
+
+    let end = input[last..].find('\n').map_or(input.len(), |i| last + i);
+    input[..end].strip_suffix('\r').unwrap_or(&input[..end])
+}
+
+/// Trims spaces and tabs. Lines that still end in `\r`, because CRLF line
+/// endings are kept, are left as is.
+fn trim_line(line: &str) -> &str {
+    match line.ends_with('\r') {
+        true => line,
+        false => line.trim_end_matches([' ', '\t']),
+    }
+}
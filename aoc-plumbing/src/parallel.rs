@@ -0,0 +1,131 @@
+use std::{fmt::Display, thread, time::Duration};
+
+use serde::Serialize;
+
+use crate::{
+    Answer, Solution, SolveContext,
+    context::{self, Phase, Timeout},
+    report::{SolveReport, Timings, timed},
+};
+
+/// Marks a problem whose parts can be solved at the same time, on separate
+/// threads sharing the parsed instance.
+///
+/// Parts only ever borrow the instance, so this is about whether it pays
+/// off: it suits days whose parts are both substantial and share nothing but
+/// the parsed data. Days that already spread each part over every core gain
+/// little from it.
+///
+/// Opting in takes implementing this marker and registering the day as
+/// parallel, after which the cli and the combined benchmarks solve both parts
+/// at once:
+///
+/// ```ignore
+/// impl ImmutableParts for Reactor {}
+///
+/// aoc_plumbing::register_day!(Reactor, parallel);
+/// ```
+///
+/// # Usage
+///
+/// ```
+/// use std::str::FromStr;
+/// use aoc_plumbing::{ImmutableParts, Problem, Solution};
+///
+/// struct Span(Vec<u64>);
+///
+/// impl FromStr for Span {
+///     type Err = std::num::ParseIntError;
+///
+///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+///         s.split_whitespace().map(str::parse).collect::<Result<_, _>>().map(Self)
+///     }
+/// }
+///
+/// impl Problem for Span {
+///     const YEAR: usize = 2025;
+///     const DAY: usize = 1;
+///     const TITLE: &'static str = "span";
+///     const README: &'static str = "";
+///
+///     type Config = ();
+///     type ProblemError = anyhow::Error;
+///     type P1 = u64;
+///     type P2 = u64;
+///
+///     fn part_one(&self) -> Result<Self::P1, Self::ProblemError> {
+///         Ok(self.0.iter().min().copied().unwrap_or_default())
+///     }
+///
+///     fn part_two(&self) -> Result<Self::P2, Self::ProblemError> {
+///         Ok(self.0.iter().max().copied().unwrap_or_default())
+///     }
+/// }
+///
+/// impl ImmutableParts for Span {}
+///
+/// let report = Span::solve_parallel_timed_with("3 1 4 1 5", ()).unwrap();
+/// assert_eq!(report.solution, Solution::new(1, 5));
+/// ```
+pub trait ImmutableParts: Sync {}
+
+/// The stack size of threads that solve parts. Matches the default stack
+/// size of the main thread, which some recursive solutions rely on.
+pub const SOLVER_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Solves both parts of a [timed] parse at the same time, part two on a
+/// thread of its own, timing each part. The current [SolveContext] is
+/// carried over to that thread, and is in [Phase::Parts] while both run and
+/// in the phase of the remaining part once the other is done. A timeout
+/// within a part is attributed to that part.
+pub(crate) fn solve_timed<I, T, G, E>(
+    (inst, parse): (Result<I, E>, Duration),
+    part_one: impl FnOnce(&I) -> Result<T, E>,
+    part_two: impl FnOnce(&I) -> Result<G, E> + Send,
+    extra: impl FnOnce(&I) -> Result<Vec<(String, Answer)>, E>,
+) -> Result<SolveReport<T, G>, E>
+where
+    I: Sync,
+    T: Display + Serialize + PartialEq,
+    G: Display + Serialize + PartialEq + Send,
+    E: From<Timeout> + Send,
+{
+    let inst = inst?;
+    context::transition(Phase::Parts)?;
+
+    let ctx = SolveContext::current();
+    let ((p1, part_one_time), (p2, part_two_time)) = thread::scope(|s| {
+        let inst = &inst;
+        let two = thread::Builder::new()
+            .stack_size(SOLVER_STACK_SIZE)
+            .spawn_scoped(s, move || match ctx {
+                Some(ctx) => {
+                    ctx.run(|| context::solve_part(Phase::PartTwo, || timed(|| part_two(inst))))
+                }
+                None => timed(|| part_two(inst)),
+            })
+            .expect("Could not start a thread for part two");
+        let one = context::solve_part(Phase::PartOne, || timed(|| part_one(inst)));
+
+        match two.join() {
+            Ok(two) => (one, two),
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    });
+
+    let (p1, p2) = (p1?, p2?);
+    context::transition(Phase::Extra)?;
+    let mut solution = Solution::new(p1, p2);
+    solution.extra = extra(&inst)?;
+    context::check()?;
+
+    Ok(SolveReport {
+        solution,
+        timings: Timings {
+            parse,
+            part_one: part_one_time,
+            part_two: part_two_time,
+            parallel: true,
+        },
+    })
+}
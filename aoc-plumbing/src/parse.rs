@@ -0,0 +1,205 @@
+use std::{error::Error, fmt::Display, io::BufRead, ops::Range};
+
+/// Where in the input a [ParseError] occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// The 1-based line number.
+    pub line: usize,
+    /// The byte range within `snippet` that is at fault.
+    pub columns: Range<usize>,
+    /// The full text of the offending line.
+    pub snippet: String,
+}
+
+impl Location {
+    /// The 1-based column of the start of the span, counted in characters.
+    pub fn column(&self) -> usize {
+        self.snippet[..self.columns.start].chars().count() + 1
+    }
+}
+
+/// An error produced while parsing an input, carrying enough information to
+/// point at the offending part of the input.
+///
+/// # Usage
+///
+/// ```
+/// use aoc_plumbing::ParseError;
+///
+/// let input = "3-5\n10-x4\n";
+/// let line = input.lines().nth(1).unwrap();
+/// let token = &line[3..];
+/// let err = ParseError::spanning(input, token, "invalid range end");
+///
+/// assert_eq!(err.to_string(), "line 2, column 4: invalid range end");
+/// assert_eq!(
+///     err.render("example.txt"),
+///     "invalid range end\n --> example.txt:2:4\n  |\n2 | 10-x4\n  |    ^^"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    message: String,
+    location: Option<Location>,
+}
+
+impl ParseError {
+    /// An error without a known location.
+    pub fn new(message: impl Display) -> Self {
+        Self {
+            message: message.to_string(),
+            location: None,
+        }
+    }
+
+    /// An error pointing at `token`, which must be a subslice of `input`. If
+    /// it is not, the error will not have a location.
+    pub fn spanning(input: &str, token: &str, message: impl Display) -> Self {
+        let mut err = Self::new(message);
+
+        let base = input.as_ptr() as usize;
+        let start = (token.as_ptr() as usize).wrapping_sub(base);
+        if start > input.len() || start + token.len() > input.len() {
+            return err;
+        }
+
+        let line_start = input[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[start..]
+            .find('\n')
+            .map_or(input.len(), |i| start + i)
+            .max(start + token.len());
+        let snippet = input[line_start..line_end].trim_end_matches(['\r', '\n']);
+        let columns_start = start - line_start;
+        let columns_end = (columns_start + token.len()).min(snippet.len());
+
+        err.location = Some(Location {
+            line: input[..line_start].matches('\n').count() + 1,
+            columns: columns_start..columns_end,
+            snippet: snippet.to_string(),
+        });
+
+        err
+    }
+
+    /// Overrides the line number, for errors produced by parsers that only
+    /// see a single line of the input.
+    pub fn on_line(mut self, line: usize) -> Self {
+        if let Some(location) = self.location.as_mut() {
+            location.line = line;
+        }
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+
+    /// Renders an annotated diagnostic, underlining the offending span with
+    /// carets. `origin` is usually the path to the input.
+    pub fn render(&self, origin: &str) -> String {
+        let Some(loc) = &self.location else {
+            return format!("{}\n --> {}", self.message, origin);
+        };
+
+        let gutter = " ".repeat(loc.line.to_string().len());
+        let pad = " ".repeat(loc.column() - 1);
+        let width = loc.snippet[loc.columns.clone()].chars().count().max(1);
+
+        format!(
+            "{}\n{} --> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}",
+            self.message,
+            &gutter[1..],
+            origin,
+            loc.line,
+            loc.column(),
+            gutter,
+            loc.line,
+            loc.snippet,
+            gutter,
+            pad,
+            "^".repeat(width),
+        )
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some(loc) => write!(
+                f,
+                "line {}, column {}: {}",
+                loc.line,
+                loc.column(),
+                self.message
+            ),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Calls `f` with each line of `reader`, without its line ending, along with
+/// its 1-based line number. A single buffer is reused for every line, so this
+/// is suitable for inputs that do not fit in memory.
+///
+/// As streamed inputs cannot be normalized up front, the lines are cleaned up
+/// like [Normalize::ALL](crate::Normalize::ALL) would, short of trimming
+/// trailing whitespace: a leading byte order mark is removed, both LF and
+/// CRLF line endings are accepted, and empty lines at the end are skipped.
+///
+/// # Usage
+///
+/// ```
+/// use aoc_plumbing::parse::for_each_line;
+///
+/// let mut total = 0;
+/// for_each_line("\u{feff}1\r\n2\n3\n\n".as_bytes(), |_, line| {
+///     total += line.parse::<u32>()?;
+///     Ok::<_, anyhow::Error>(())
+/// })
+/// .unwrap();
+///
+/// assert_eq!(total, 6);
+/// ```
+pub fn for_each_line<E>(
+    mut reader: impl BufRead,
+    mut f: impl FnMut(usize, &str) -> Result<(), E>,
+) -> Result<(), E>
+where
+    E: From<std::io::Error>,
+{
+    let mut buf = String::new();
+    let mut line = 0;
+    // empty lines are only passed on once it is clear that they are not at
+    // the end
+    let mut empty = 0;
+
+    loop {
+        buf.clear();
+        if reader.read_line(&mut buf)? == 0 {
+            return Ok(());
+        }
+
+        let mut l = buf.trim_end_matches(['\r', '\n']);
+        if line == 0 {
+            l = l.strip_prefix('\u{feff}').unwrap_or(l);
+        }
+        line += 1;
+
+        if l.is_empty() {
+            empty += 1;
+            continue;
+        }
+
+        for i in line - empty..line {
+            f(i, "")?;
+        }
+        empty = 0;
+        f(line, l)?;
+    }
+}
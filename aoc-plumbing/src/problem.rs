@@ -1,11 +1,43 @@
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, io::BufRead, str::FromStr};
 
-use serde::Serialize;
+use serde::{Serialize, Serializer, de::DeserializeOwned};
+
+use crate::{
+    Answer, Example, ImmutableParts, Normalize,
+    context::Timeout,
+    parallel,
+    report::{self, SolveReport, timed},
+    validate::Diagnostic,
+};
+
+/// The answer for a part that a problem does not have, such as the second part
+/// of the final day.
+///
+/// This is displayed as `n/a` and serialized as `null`, though a [Solution]
+/// leaves the part out of its plaintext output altogether.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NoAnswer;
+
+impl Display for NoAnswer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "n/a")
+    }
+}
+
+impl Serialize for NoAnswer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_none()
+    }
+}
 
 /// This struct enables printing a given solution in either plaintext or JSON,
 /// depending on the presence of the `AOC_OUTPUT_JSON` ENV var. Its main purpose
 /// is to standardize the output for consuption by the CI system.
 ///
+/// Besides the two parts, a solution may carry any number of named extra
+/// answers, which are only output when present. Parts the problem does not
+/// have, see [NoAnswer], are left out of the plaintext output.
+///
 /// # Usage
 ///
 /// ```
@@ -22,6 +54,14 @@ use serde::Serialize;
 ///     serde_json::to_string(&s).unwrap(),
 ///     "{\"part_one\":\"hello world\",\"part_two\":12345}".to_string()
 /// );
+///
+/// let s = Solution::new(1, 2).with_extra("steps", 3);
+///
+/// assert_eq!(s.to_string(), "part 1: 1\npart 2: 2\nsteps: 3");
+/// assert_eq!(
+///     serde_json::to_string(&s).unwrap(),
+///     "{\"part_one\":1,\"part_two\":2,\"extra\":{\"steps\":3}}".to_string()
+/// );
 /// ```
 #[derive(Debug, Serialize, PartialEq)]
 pub struct Solution<T, G>
@@ -31,19 +71,30 @@ where
 {
     pub part_one: T,
     pub part_two: G,
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_extra"
+    )]
+    pub extra: Vec<(String, Answer)>,
 }
 
-/// The default implementation of `Solution` is as follows:
+/// The default implementation of `Solution` has neither part:
 /// ```
-/// use aoc_plumbing::Solution;
+/// use aoc_plumbing::{NoAnswer, Solution};
 /// let default = Solution::default();
-/// let expected = Solution::new("not implemented", "not implemented");
+/// let expected = Solution::new(NoAnswer, NoAnswer);
 ///
 /// assert_eq!(default, expected);
+/// assert_eq!(default.to_string(), "");
+/// assert_eq!(Solution::new(6, NoAnswer).to_string(), "part 1: 6");
+/// assert_eq!(
+///     serde_json::to_string(&default).unwrap(),
+///     "{\"part_one\":null,\"part_two\":null}".to_string()
+/// );
 /// ```
-impl Default for Solution<&str, &str> {
+impl Default for Solution<NoAnswer, NoAnswer> {
     fn default() -> Self {
-        Solution::new("not implemented", "not implemented")
+        Solution::new(NoAnswer, NoAnswer)
     }
 }
 
@@ -53,7 +104,17 @@ where
     G: Display + Serialize + PartialEq,
 {
     pub fn new(part_one: T, part_two: G) -> Self {
-        Self { part_one, part_two }
+        Self {
+            part_one,
+            part_two,
+            extra: Vec::default(),
+        }
+    }
+
+    /// Adds a named extra answer.
+    pub fn with_extra(mut self, name: impl Into<String>, value: impl Into<Answer>) -> Self {
+        self.extra.push((name.into(), value.into()));
+        self
     }
 }
 
@@ -63,10 +124,27 @@ where
     G: Display + Serialize + PartialEq,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "part 1: {}\npart 2: {}", self.part_one, self.part_two)
+        let mut lines = Vec::new();
+        if !is_missing(&self.part_one) {
+            lines.push(format!("part 1: {}", self.part_one));
+        }
+        if !is_missing(&self.part_two) {
+            lines.push(format!("part 2: {}", self.part_two));
+        }
+        for (name, value) in &self.extra {
+            lines.push(format!("{}: {}", name, value));
+        }
+
+        write!(f, "{}", lines.join("\n"))
     }
 }
 
+/// Whether `answer` is for a part the problem does not have, which is how
+/// [NoAnswer] and [Answer::Missing] serialize.
+fn is_missing(answer: &impl Serialize) -> bool {
+    serde_json::to_value(answer).is_ok_and(|value| value.is_null())
+}
+
 impl<T, G> From<(T, G)> for Solution<T, G>
 where
     T: Display + Serialize + PartialEq,
@@ -77,44 +155,235 @@ where
     }
 }
 
+fn serialize_extra<S: Serializer>(
+    extra: &[(String, Answer)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(extra.iter().map(|(k, v)| (k, v)))
+}
+
+/// A puzzle for a given day.
+///
+/// The parts of a problem are independent and repeatable: they only borrow
+/// the parsed instance, so they can be solved in any order, any number of
+/// times, and either one can be solved without paying for the other. Work
+/// that both parts need belongs in parsing.
+///
+/// Problems with only one part, like the final day, should use [NoAnswer] as
+/// their `P2` and return it from [Problem::part_two]. Any additional answers
+/// can be reported by name via [Problem::extra].
+///
+/// # Usage
+///
+/// ```
+/// use std::str::FromStr;
+/// use aoc_plumbing::{Answer, NoAnswer, Problem, Solution};
+///
+/// struct Finale(Vec<u64>);
+///
+/// impl FromStr for Finale {
+///     type Err = std::num::ParseIntError;
+///
+///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+///         s.lines().map(str::parse).collect::<Result<_, _>>().map(Self)
+///     }
+/// }
+///
+/// impl Problem for Finale {
+///     const YEAR: usize = 2025;
+///     const DAY: usize = 12;
+///     const TITLE: &'static str = "finale";
+///     const README: &'static str = "";
+///
+///     type Config = ();
+///     type ProblemError = anyhow::Error;
+///     type P1 = u64;
+///     type P2 = NoAnswer;
+///
+///     fn part_one(&self) -> Result<Self::P1, Self::ProblemError> {
+///         Ok(self.0.iter().sum())
+///     }
+///
+///     fn part_two(&self) -> Result<Self::P2, Self::ProblemError> {
+///         Ok(NoAnswer)
+///     }
+///
+///     fn extra(&self) -> Result<Vec<(String, Answer)>, Self::ProblemError> {
+///         Ok(vec![("largest".into(), self.0.iter().max().copied().unwrap_or_default().into())])
+///     }
+/// }
+///
+/// let solution = Finale::solve("1\n2\n3").unwrap();
+/// assert_eq!(solution, Solution::new(6, NoAnswer).with_extra("largest", 3));
+/// assert_eq!(solution.to_string(), "part 1: 6\nlargest: 3");
+/// ```
 pub trait Problem: FromStr {
+    /// The year of the event the problem is from.
+    const YEAR: usize;
     const DAY: usize;
     const TITLE: &'static str;
     const README: &'static str;
+    /// The normalizations applied to raw inputs before they are parsed.
+    const NORMALIZE: Normalize = Normalize::ALL;
+
+    /// Tunable puzzle parameters, such as the number of rounds to simulate.
+    /// Problems without any should use `()`.
+    ///
+    /// The default value describes the actual puzzle. Fields can be
+    /// overridden by name at runtime via [Params](crate::Params).
+    type Config: Default + Serialize + DeserializeOwned;
+    /// The error type for this problem. This must be able to hold a [Timeout],
+    /// which solves return when they exceed the deadline of the current
+    /// [SolveContext](crate::context::SolveContext).
+    type ProblemError: Send + Sync + From<<Self as FromStr>::Err> + From<Timeout> + 'static;
+    type P1: Display + Serialize + PartialEq + Into<Answer>;
+    type P2: Display + Serialize + PartialEq + Into<Answer>;
+
+    /// Solves the first part. This must not depend on whether, or how often,
+    /// either part has been solved before.
+    fn part_one(&self) -> Result<Self::P1, Self::ProblemError>;
+    /// Solves the second part, see [Problem::part_one].
+    fn part_two(&self) -> Result<Self::P2, Self::ProblemError>;
 
-    type ProblemError: Send + Sync + From<<Self as FromStr>::Err> + 'static;
-    type P1: Display + Serialize + PartialEq;
-    type P2: Display + Serialize + PartialEq;
+    /// The examples from the puzzle description and their expected answers.
+    /// These are turned into tests by [example_tests](crate::example_tests).
+    fn examples() -> Vec<Example> {
+        Vec::default()
+    }
 
-    fn part_one(&mut self) -> Result<Self::P1, Self::ProblemError>;
-    fn part_two(&mut self) -> Result<Self::P2, Self::ProblemError>;
+    /// Named answers beyond the two parts, if any. These are computed after
+    /// both parts.
+    fn extra(&self) -> Result<Vec<(String, Answer)>, Self::ProblemError> {
+        Ok(Vec::default())
+    }
 
+    /// Checks the parsed instance for anything that would make solving it
+    /// fail, or produce nonsense, without solving it. Inputs that do not even
+    /// parse should be rejected with a [ParseError](crate::ParseError)
+    /// instead.
+    fn validate(&self) -> Vec<Diagnostic> {
+        Vec::default()
+    }
+
+    /// Normalizes the input according to [Problem::NORMALIZE], then parses
+    /// it for the actual puzzle.
     fn instance(raw_input: &str) -> Result<Self, <Self as FromStr>::Err> {
+        Self::instance_with(&Self::NORMALIZE.apply(raw_input), Self::Config::default())
+    }
+
+    /// Parses the input for the puzzle described by `config`. Problems whose
+    /// [Problem::Config] is not `()` must override this, as the default
+    /// implementation ignores `config`.
+    ///
+    /// The input is used as is, callers are expected to have normalized it
+    /// already. [Problem::instance] and the `solve` methods do so.
+    fn instance_with(
+        raw_input: &str,
+        _config: Self::Config,
+    ) -> Result<Self, <Self as FromStr>::Err> {
         Self::from_str(raw_input)
     }
 
+    /// Builds an instance by reading the input incrementally, for inputs that
+    /// are too large to hold in memory. This is optional, and the default
+    /// implementation returns `None` without reading anything.
+    ///
+    /// Implementations will usually have to do most of the work of solving
+    /// while reading, keeping only what the parts need. The input is not
+    /// normalized, but [for_each_line](crate::parse::for_each_line) takes
+    /// care of most of what [Problem::NORMALIZE] would.
+    fn from_reader(
+        _reader: impl BufRead,
+        _config: Self::Config,
+    ) -> Option<Result<Self, Self::ProblemError>> {
+        None
+    }
+
     fn solve(raw_input: &str) -> Result<Solution<Self::P1, Self::P2>, Self::ProblemError> {
-        let mut inst = Self::instance(raw_input)?;
-        Ok(Solution::new(inst.part_one()?, inst.part_two()?))
+        Self::solve_with(raw_input, Self::Config::default())
     }
 
-    fn problem_label() -> String {
-        format!(
-            "{:03} {}",
-            <Self as Problem>::padded_day(),
-            <Self as Problem>::TITLE
+    fn solve_with(
+        raw_input: &str,
+        config: Self::Config,
+    ) -> Result<Solution<Self::P1, Self::P2>, Self::ProblemError> {
+        Self::solve_timed_with(raw_input, config).map(|report| report.solution)
+    }
+
+    /// Like [Problem::solve], but also records the wall time spent parsing
+    /// the input, which includes normalizing it, and solving each part.
+    /// Extra answers are not timed.
+    fn solve_timed(raw_input: &str) -> Result<SolveReport<Self::P1, Self::P2>, Self::ProblemError> {
+        Self::solve_timed_with(raw_input, Self::Config::default())
+    }
+
+    fn solve_timed_with(
+        raw_input: &str,
+        config: Self::Config,
+    ) -> Result<SolveReport<Self::P1, Self::P2>, Self::ProblemError> {
+        let (input, normalize) = report::normalized(Self::NORMALIZE, raw_input);
+        let (inst, parse) = timed(|| Ok(Self::instance_with(&input, config)?));
+        report::solve_timed(
+            (inst, normalize + parse),
+            Self::part_one,
+            Self::part_two,
+            Self::extra,
         )
     }
 
+    /// Like [Problem::solve_timed_with], but solves both parts at the same
+    /// time, see [ImmutableParts].
+    fn solve_parallel_timed_with(
+        raw_input: &str,
+        config: Self::Config,
+    ) -> Result<SolveReport<Self::P1, Self::P2>, Self::ProblemError>
+    where
+        Self: ImmutableParts,
+        Self::P2: Send,
+    {
+        let (input, normalize) = report::normalized(Self::NORMALIZE, raw_input);
+        let (inst, parse) = timed(|| Ok(Self::instance_with(&input, config)?));
+        parallel::solve_timed(
+            (inst, normalize + parse),
+            Self::part_one,
+            Self::part_two,
+            Self::extra,
+        )
+    }
+
+    /// Like [Problem::solve_timed_with], but builds the instance via
+    /// [Problem::from_reader]. The parse time includes reading the input.
+    #[allow(clippy::type_complexity)]
+    fn solve_reader(
+        reader: impl BufRead,
+        config: Self::Config,
+    ) -> Option<Result<SolveReport<Self::P1, Self::P2>, Self::ProblemError>> {
+        let (inst, parse) = timed(|| Self::from_reader(reader, config));
+        Some(report::solve_timed(
+            (inst?, parse),
+            Self::part_one,
+            Self::part_two,
+            Self::extra,
+        ))
+    }
+
+    fn problem_label() -> String {
+        problem_label(<Self as Problem>::DAY, <Self as Problem>::TITLE)
+    }
+
     fn padded_day() -> String {
         format!("{:03}", <Self as Problem>::DAY)
     }
 
     fn long_description() -> String {
-        format!(
-            "{} {}",
-            <Self as Problem>::padded_day(),
-            <Self as Problem>::README
-        )
+        long_description(<Self as Problem>::DAY, <Self as Problem>::README)
     }
 }
+
+pub(crate) fn problem_label(day: usize, title: &str) -> String {
+    format!("{:03} {}", day, title)
+}
+
+pub(crate) fn long_description(day: usize, readme: &str) -> String {
+    format!("{:03} {}", day, readme)
+}
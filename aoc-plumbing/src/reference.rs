@@ -0,0 +1,204 @@
+use std::{error::Error, fmt::Display};
+
+use crate::{Answer, DynProblem, Params, example::Part, generate::Generate};
+use anyhow::{Context, Result};
+
+/// A slow but obviously correct solution to a problem, used to check the
+/// real solution on random inputs via [Differential].
+///
+/// # Usage
+///
+/// ```
+/// use aoc_plumbing::{Answer, generate::Generate, reference::{Differential, Reference}};
+/// use rand::Rng;
+/// # use std::str::FromStr;
+/// # use aoc_plumbing::{Problem, dynamic::erase};
+/// # struct Triangle(u64);
+/// # impl FromStr for Triangle {
+/// #     type Err = anyhow::Error;
+/// #     fn from_str(s: &str) -> Result<Self, Self::Err> { Ok(Self(s.trim().parse()?)) }
+/// # }
+/// # impl Problem for Triangle {
+/// #     const YEAR: usize = 2025;
+/// #     const DAY: usize = 1;
+/// #     const TITLE: &'static str = "triangle";
+/// #     const README: &'static str = "";
+/// #     type Config = ();
+/// #     type ProblemError = anyhow::Error;
+/// #     type P1 = u64;
+/// #     type P2 = u64;
+/// #     fn part_one(&self) -> Result<Self::P1, Self::ProblemError> { Ok(self.0 * (self.0 + 1) / 2) }
+/// #     fn part_two(&self) -> Result<Self::P2, Self::ProblemError> { Ok(self.0 * self.0) }
+/// # }
+///
+/// impl Generate for Triangle {
+///     type Size = u64;
+///
+///     fn generate(rng: &mut impl Rng, size: &Self::Size) -> String {
+///         rng.random_range(0..*size).to_string()
+///     }
+/// }
+///
+/// impl Reference for Triangle {
+///     fn small() -> Self::Size {
+///         1000
+///     }
+///
+///     fn reference_part_one(input: &str) -> anyhow::Result<Answer> {
+///         Ok((1..=input.parse::<u64>()?).sum::<u64>().into())
+///     }
+///
+///     fn reference_part_two(input: &str) -> anyhow::Result<Answer> {
+///         let n = input.parse::<u64>()?;
+///         Ok((0..n).map(|_| n).sum::<u64>().into())
+///     }
+/// }
+///
+/// assert!(Differential::default().run::<Triangle>(&*erase::<Triangle>()).is_ok());
+/// ```
+pub trait Reference: Generate {
+    /// A size for generated inputs that the reference can solve quickly.
+    fn small() -> Self::Size;
+
+    fn reference_part_one(input: &str) -> Result<Answer>;
+    fn reference_part_two(input: &str) -> Result<Answer>;
+}
+
+/// The first input on which a solution disagreed with its [Reference].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    /// The seed that generated the input.
+    pub seed: u64,
+    pub input: String,
+    pub part: Part,
+    pub expected: Answer,
+    pub actual: Answer,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} was {}, but the reference gave {} (seed {}) for input:\n{}",
+            self.part, self.actual, self.expected, self.seed, self.input
+        )
+    }
+}
+
+impl Error for Mismatch {}
+
+/// Runs a solution and its [Reference] on many random inputs, stopping at
+/// the first disagreement.
+///
+/// Each case is generated from its own seed, counting up from `seed`, so the
+/// input of a [Mismatch] can be reproduced on its own from the seed it
+/// reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Differential {
+    pub cases: u64,
+    pub seed: u64,
+}
+
+impl Default for Differential {
+    fn default() -> Self {
+        Self {
+            cases: 256,
+            seed: 0,
+        }
+    }
+}
+
+impl Differential {
+    /// The defaults, overridden by the `AOC_REFERENCE_CASES` and
+    /// `AOC_REFERENCE_SEED` env vars if they are set.
+    pub fn from_env() -> Result<Self> {
+        let mut differential = Self::default();
+
+        if let Ok(cases) = std::env::var("AOC_REFERENCE_CASES") {
+            differential.cases = cases.parse().context("Invalid AOC_REFERENCE_CASES")?;
+        }
+
+        if let Ok(seed) = std::env::var("AOC_REFERENCE_SEED") {
+            differential.seed = seed.parse().context("Invalid AOC_REFERENCE_SEED")?;
+        }
+
+        Ok(differential)
+    }
+
+    /// Checks `problem` against the reference `R`. Disagreements are
+    /// reported as a [Mismatch].
+    pub fn run<R: Reference>(&self, problem: &dyn DynProblem) -> Result<()> {
+        for seed in (0..self.cases).map(|case| self.seed.wrapping_add(case)) {
+            let input = R::generate_seeded(seed, &R::small());
+            let failed = || format!("Failed on seed {} for input:\n{}", seed, input);
+
+            let inst = problem
+                .instance_with(&input, &Params::default())
+                .with_context(failed)?;
+
+            for part in [Part::One, Part::Two] {
+                let (actual, expected) = match part {
+                    Part::One => (inst.part_one(), R::reference_part_one(&input)),
+                    Part::Two => (inst.part_two(), R::reference_part_two(&input)),
+                };
+                let actual = actual.with_context(failed)?;
+                let expected = expected
+                    .context("The reference failed")
+                    .with_context(failed)?;
+
+                if actual != expected {
+                    return Err(Mismatch {
+                        seed,
+                        input: input.clone(),
+                        part,
+                        expected,
+                        actual,
+                    }
+                    .into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Generates a unit test that checks a problem against its [Reference] with
+/// [Differential::from_env]. The problem is named as for
+/// [register_day](crate::register_day):
+///
+/// ```ignore
+/// aoc_plumbing::reference_tests!(GiftShop);
+/// ```
+#[macro_export]
+macro_rules! reference_tests {
+    (@tests $reference:ty, $erase:block) => {
+        #[cfg(test)]
+        mod reference_tests {
+            use super::*;
+
+            #[test]
+            fn matches_reference() {
+                let problem: ::std::boxed::Box<dyn $crate::DynProblem> = $erase;
+                $crate::reference::Differential::from_env()
+                    .unwrap()
+                    .run::<$reference>(&*problem)
+                    .unwrap();
+            }
+        }
+    };
+    ($problem:ident<'_>) => {
+        $crate::reference_tests!(@tests $problem<'static>, {
+            struct Family;
+
+            impl $crate::BorrowedFamily for Family {
+                type Problem<'a> = $problem<'a>;
+            }
+
+            $crate::dynamic::erase_borrowed::<Family>()
+        });
+    };
+    ($problem:ty) => {
+        $crate::reference_tests!(@tests $problem, { $crate::dynamic::erase::<$problem>() });
+    };
+}
@@ -0,0 +1,361 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::OnceLock,
+};
+
+use linkme::distributed_slice;
+
+use crate::{
+    Problem,
+    dynamic::{DynProblem, erase},
+    generate::DynGenerate,
+    visualize::DynVisualize,
+};
+
+/// Every day registered via [register_day](crate::register_day) in any crate
+/// linked into the final binary.
+#[distributed_slice]
+pub static DAYS: [fn() -> Box<dyn DynProblem>];
+
+/// Every input generator registered via
+/// [register_generator](crate::register_generator).
+#[distributed_slice]
+pub static GENERATORS: [fn() -> Box<dyn DynGenerate>];
+
+/// Every visualization registered via
+/// [register_visualizer](crate::register_visualizer).
+#[distributed_slice]
+pub static VISUALIZERS: [fn() -> Box<dyn DynVisualize>];
+
+/// Registers a [Problem] so that it is discovered by [Registry::global].
+///
+/// This should be invoked once, next to the `Problem` impl, in each day's
+/// crate:
+///
+/// ```ignore
+/// aoc_plumbing::register_day!(SecretEntrance);
+/// ```
+///
+/// A [BorrowedProblem](crate::BorrowedProblem) is registered by naming it
+/// with an elided lifetime instead:
+///
+/// ```ignore
+/// aoc_plumbing::register_day!(SecretEntrance<'_>);
+/// ```
+///
+/// A problem implementing [ImmutableParts](crate::ImmutableParts) can be
+/// registered as `parallel`, so that its parts are solved at the same time:
+///
+/// ```ignore
+/// aoc_plumbing::register_day!(SecretEntrance<'_>, parallel);
+/// ```
+///
+/// Note that a crate is only linked if it is referred to by something, which
+/// is what the `aoc-solutions` crate is for.
+#[macro_export]
+macro_rules! register_day {
+    ($problem:ident<'_>) => {
+        $crate::register_day!(@borrowed $problem, erase_borrowed);
+    };
+    ($problem:ident<'_>, parallel) => {
+        $crate::register_day!(@borrowed $problem, erase_borrowed_parallel);
+    };
+    ($problem:ty) => {
+        $crate::register_day!(@owned $problem, erase);
+    };
+    ($problem:ty, parallel) => {
+        $crate::register_day!(@owned $problem, erase_parallel);
+    };
+    (@borrowed $problem:ident, $erase:ident) => {
+        const _: () = {
+            struct Family;
+
+            impl $crate::BorrowedFamily for Family {
+                type Problem<'a> = $problem<'a>;
+            }
+
+            #[$crate::linkme::distributed_slice($crate::registry::DAYS)]
+            #[linkme(crate = $crate::linkme)]
+            static DAY: fn() -> ::std::boxed::Box<dyn $crate::DynProblem> =
+                $crate::dynamic::$erase::<Family>;
+        };
+    };
+    (@owned $problem:ty, $erase:ident) => {
+        const _: () = {
+            #[$crate::linkme::distributed_slice($crate::registry::DAYS)]
+            #[linkme(crate = $crate::linkme)]
+            static DAY: fn() -> ::std::boxed::Box<dyn $crate::DynProblem> =
+                $crate::dynamic::$erase::<$problem>;
+        };
+    };
+}
+
+/// Registers the [Generate](crate::generate::Generate) implementation of a
+/// problem so that it is discovered by [Registry::global]. The problem is
+/// named as for [register_day](crate::register_day):
+///
+/// ```ignore
+/// aoc_plumbing::register_generator!(SecretEntrance<'_>);
+/// ```
+#[macro_export]
+macro_rules! register_generator {
+    ($problem:ident<'_>) => {
+        const _: () = {
+            fn make() -> ::std::boxed::Box<dyn $crate::generate::DynGenerate> {
+                $crate::generate::erase_generator::<$problem<'static>>(
+                    <$problem<'static> as $crate::BorrowedProblem<'static>>::YEAR,
+                    <$problem<'static> as $crate::BorrowedProblem<'static>>::DAY,
+                )
+            }
+
+            #[$crate::linkme::distributed_slice($crate::registry::GENERATORS)]
+            #[linkme(crate = $crate::linkme)]
+            static GENERATOR: fn() -> ::std::boxed::Box<dyn $crate::generate::DynGenerate> = make;
+        };
+    };
+    ($problem:ty) => {
+        const _: () = {
+            fn make() -> ::std::boxed::Box<dyn $crate::generate::DynGenerate> {
+                $crate::generate::erase_generator::<$problem>(
+                    <$problem as $crate::Problem>::YEAR,
+                    <$problem as $crate::Problem>::DAY,
+                )
+            }
+
+            #[$crate::linkme::distributed_slice($crate::registry::GENERATORS)]
+            #[linkme(crate = $crate::linkme)]
+            static GENERATOR: fn() -> ::std::boxed::Box<dyn $crate::generate::DynGenerate> = make;
+        };
+    };
+}
+
+/// Registers the [Visualize](crate::visualize::Visualize) implementation of
+/// a problem so that it is discovered by [Registry::global]. The problem is
+/// named as for [register_day](crate::register_day):
+///
+/// ```ignore
+/// aoc_plumbing::register_visualizer!(PrintingDepartment);
+/// ```
+#[macro_export]
+macro_rules! register_visualizer {
+    ($problem:ident<'_>) => {
+        const _: () = {
+            struct Family;
+
+            impl $crate::BorrowedFamily for Family {
+                type Problem<'a> = $problem<'a>;
+            }
+
+            #[$crate::linkme::distributed_slice($crate::registry::VISUALIZERS)]
+            #[linkme(crate = $crate::linkme)]
+            static VISUALIZER: fn() -> ::std::boxed::Box<dyn $crate::visualize::DynVisualize> =
+                $crate::visualize::erase_borrowed_visualizer::<Family>;
+        };
+    };
+    ($problem:ty) => {
+        const _: () = {
+            #[$crate::linkme::distributed_slice($crate::registry::VISUALIZERS)]
+            #[linkme(crate = $crate::linkme)]
+            static VISUALIZER: fn() -> ::std::boxed::Box<dyn $crate::visualize::DynVisualize> =
+                $crate::visualize::erase_visualizer::<$problem>;
+        };
+    };
+}
+
+/// A runtime lookup of boxed solvers keyed by year and day.
+///
+/// # Usage
+///
+/// ```
+/// # use std::str::FromStr;
+/// # use aoc_plumbing::Problem;
+/// # struct Echo(i64);
+/// # impl FromStr for Echo {
+/// #     type Err = anyhow::Error;
+/// #     fn from_str(s: &str) -> Result<Self, Self::Err> { Ok(Self(s.trim().parse()?)) }
+/// # }
+/// # impl Problem for Echo {
+/// #     const YEAR: usize = 2025;
+/// #     const DAY: usize = 3;
+/// #     const TITLE: &'static str = "echo";
+/// #     const README: &'static str = "";
+/// #     type Config = ();
+/// #     type ProblemError = anyhow::Error;
+/// #     type P1 = i64;
+/// #     type P2 = i64;
+/// #     fn part_one(&self) -> Result<Self::P1, Self::ProblemError> { Ok(self.0) }
+/// #     fn part_two(&self) -> Result<Self::P2, Self::ProblemError> { Ok(-self.0) }
+/// # }
+/// use aoc_plumbing::Registry;
+///
+/// let registry = Registry::default().with::<Echo>();
+///
+/// assert_eq!(registry.years().collect::<Vec<_>>(), vec![2025]);
+/// assert_eq!(registry.days(2025).collect::<Vec<_>>(), vec![3]);
+/// assert!(registry.get(2025, 1).is_none());
+/// assert!(registry.get(2024, 3).is_none());
+///
+/// let solution = registry.get(2025, 3).unwrap().solve("7").unwrap();
+/// assert_eq!(solution.part_two.to_string(), "-7");
+/// ```
+#[derive(Default)]
+pub struct Registry {
+    problems: BTreeMap<(usize, usize), Box<dyn DynProblem>>,
+    generators: BTreeMap<(usize, usize), Box<dyn DynGenerate>>,
+    visualizers: BTreeMap<(usize, usize), Box<dyn DynVisualize>>,
+}
+
+impl Registry {
+    /// Collects every day registered via [register_day](crate::register_day),
+    /// along with any generators and visualizations registered via
+    /// [register_generator](crate::register_generator) and
+    /// [register_visualizer](crate::register_visualizer).
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than one problem, generator or visualization was
+    /// registered for the same day of the same year.
+    pub fn discover() -> Self {
+        let mut registry = Self::default();
+
+        for make in DAYS {
+            let problem = make();
+            if let Some(existing) = registry.get(problem.year(), problem.day()) {
+                panic!(
+                    "Day {} of {} registered by both '{}' and '{}'",
+                    problem.day(),
+                    problem.year(),
+                    existing.title(),
+                    problem.title()
+                );
+            }
+            registry.insert(problem);
+        }
+
+        for make in GENERATORS {
+            let generator = make();
+            if registry
+                .generator(generator.year(), generator.day())
+                .is_some()
+            {
+                panic!(
+                    "Day {} of {} has more than one generator",
+                    generator.day(),
+                    generator.year()
+                );
+            }
+            registry.insert_generator(generator);
+        }
+
+        for make in VISUALIZERS {
+            let visualizer = make();
+            if registry
+                .visualizer(visualizer.year(), visualizer.day())
+                .is_some()
+            {
+                panic!(
+                    "Day {} of {} has more than one visualization",
+                    visualizer.day(),
+                    visualizer.year()
+                );
+            }
+            registry.insert_visualizer(visualizer);
+        }
+
+        registry
+    }
+
+    /// The lazily [discovered](Registry::discover) registry of every linked
+    /// day.
+    pub fn global() -> &'static Self {
+        static REGISTRY: OnceLock<Registry> = OnceLock::new();
+        REGISTRY.get_or_init(Self::discover)
+    }
+
+    /// Registers `P`, replacing any problem previously registered for the
+    /// same day of the same year.
+    pub fn register<P>(&mut self) -> &mut Self
+    where
+        P: Problem + 'static,
+        <P as Problem>::ProblemError: Into<anyhow::Error>,
+    {
+        self.insert(erase::<P>())
+    }
+
+    /// Registers an already erased problem, replacing any problem previously
+    /// registered for the same day of the same year.
+    pub fn insert(&mut self, problem: Box<dyn DynProblem>) -> &mut Self {
+        self.problems
+            .insert((problem.year(), problem.day()), problem);
+        self
+    }
+
+    /// Builder-style version of [Registry::register].
+    pub fn with<P>(mut self) -> Self
+    where
+        P: Problem + 'static,
+        <P as Problem>::ProblemError: Into<anyhow::Error>,
+    {
+        self.register::<P>();
+        self
+    }
+
+    pub fn get(&self, year: usize, day: usize) -> Option<&dyn DynProblem> {
+        self.problems.get(&(year, day)).map(AsRef::as_ref)
+    }
+
+    /// Registers an already erased generator, replacing any generator
+    /// previously registered for the same day of the same year.
+    pub fn insert_generator(&mut self, generator: Box<dyn DynGenerate>) -> &mut Self {
+        self.generators
+            .insert((generator.year(), generator.day()), generator);
+        self
+    }
+
+    pub fn generator(&self, year: usize, day: usize) -> Option<&dyn DynGenerate> {
+        self.generators.get(&(year, day)).map(AsRef::as_ref)
+    }
+
+    /// Registers an already erased visualization, replacing any visualization
+    /// previously registered for the same day of the same year.
+    pub fn insert_visualizer(&mut self, visualizer: Box<dyn DynVisualize>) -> &mut Self {
+        self.visualizers
+            .insert((visualizer.year(), visualizer.day()), visualizer);
+        self
+    }
+
+    pub fn visualizer(&self, year: usize, day: usize) -> Option<&dyn DynVisualize> {
+        self.visualizers.get(&(year, day)).map(AsRef::as_ref)
+    }
+
+    /// Iterates over the registered problems in order of year, then day.
+    pub fn iter(&self) -> impl Iterator<Item = &dyn DynProblem> {
+        self.problems.values().map(AsRef::as_ref)
+    }
+
+    /// Iterates over the problems registered for `year` in order of day.
+    pub fn iter_year(&self, year: usize) -> impl Iterator<Item = &dyn DynProblem> {
+        self.problems
+            .range((year, 0)..(year + 1, 0))
+            .map(|(_, problem)| problem.as_ref())
+    }
+
+    /// The years with at least one registered problem, in order.
+    pub fn years(&self) -> impl Iterator<Item = usize> + use<> {
+        let years: BTreeSet<_> = self.problems.keys().map(|&(year, _)| year).collect();
+        years.into_iter()
+    }
+
+    /// The days registered for `year`, in order.
+    pub fn days(&self, year: usize) -> impl Iterator<Item = usize> + '_ {
+        self.iter_year(year).map(|problem| problem.day())
+    }
+
+    pub fn len(&self) -> usize {
+        self.problems.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
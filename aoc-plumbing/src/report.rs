@@ -0,0 +1,168 @@
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use serde::{Serialize, Serializer, ser::SerializeStruct};
+
+use crate::{
+    Answer, Normalize, Solution,
+    context::{self, Phase, Timeout},
+};
+
+/// Wall time spent in each phase of a solve.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part_one: Duration,
+    pub part_two: Duration,
+    /// Whether the parts were solved at the same time, in which case only the
+    /// slower one counts towards the total.
+    pub parallel: bool,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        if self.parallel {
+            self.parse + self.part_one.max(self.part_two)
+        } else {
+            self.parse + self.part_one + self.part_two
+        }
+    }
+}
+
+impl Display for Timings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "parse:  {:?}\npart 1: {:?}\npart 2: {:?}\ntotal:  {:?}",
+            self.parse,
+            self.part_one,
+            self.part_two,
+            self.total()
+        )
+    }
+}
+
+/// Durations are serialized as whole nanoseconds so they can be consumed
+/// without having to parse a unit suffix.
+impl Serialize for Timings {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("Timings", 4)?;
+        s.serialize_field("parse_ns", &nanos(self.parse))?;
+        s.serialize_field("part_one_ns", &nanos(self.part_one))?;
+        s.serialize_field("part_two_ns", &nanos(self.part_two))?;
+        s.serialize_field("total_ns", &nanos(self.total()))?;
+        s.end()
+    }
+}
+
+/// A [Solution] along with how long each phase took to produce it.
+///
+/// # Usage
+///
+/// ```
+/// use std::time::Duration;
+/// use aoc_plumbing::{Solution, SolveReport, Timings};
+///
+/// let report = SolveReport {
+///     solution: Solution::new(1, 2),
+///     timings: Timings {
+///         parse: Duration::from_micros(5),
+///         part_one: Duration::from_micros(10),
+///         part_two: Duration::from_micros(20),
+///         parallel: false,
+///     },
+/// };
+///
+/// assert_eq!(
+///     report.to_string(),
+///     "part 1: 1\npart 2: 2\n\nparse:  5µs\npart 1: 10µs\npart 2: 20µs\ntotal:  35µs"
+/// );
+/// assert_eq!(
+///     serde_json::to_string(&report).unwrap(),
+///     concat!(
+///         "{\"part_one\":1,\"part_two\":2,\"timings\":",
+///         "{\"parse_ns\":5000,\"part_one_ns\":10000,\"part_two_ns\":20000,\"total_ns\":35000}}"
+///     )
+/// );
+/// ```
+#[derive(Debug, Serialize, PartialEq)]
+pub struct SolveReport<T, G>
+where
+    T: Display + Serialize + PartialEq,
+    G: Display + Serialize + PartialEq,
+{
+    #[serde(flatten)]
+    pub solution: Solution<T, G>,
+    pub timings: Timings,
+}
+
+impl<T, G> Display for SolveReport<T, G>
+where
+    T: Display + Serialize + PartialEq,
+    G: Display + Serialize + PartialEq,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\n\n{}", self.solution, self.timings)
+    }
+}
+
+/// Runs `f`, returning its result along with the wall time it took.
+pub(crate) fn timed<R>(f: impl FnOnce() -> R) -> (R, Duration) {
+    let start = Instant::now();
+    let ret = f();
+    (ret, start.elapsed())
+}
+
+/// Normalizes an input given in full, returning how long it took. Every
+/// solve of such an input normalizes it through this, whatever the shape of
+/// the problem, and counts the time towards parsing.
+pub(crate) fn normalized(normalize: Normalize, raw_input: &str) -> (Cow<'_, str>, Duration) {
+    timed(|| normalize.apply(raw_input))
+}
+
+/// Solves both parts of a [timed] parse, timing each part. This is shared by
+/// the owned and borrowed problem shapes.
+///
+/// The current [context](crate::context) is checked between each phase, so a
+/// solve that runs past its deadline fails with a [Timeout] naming the phase
+/// that was running.
+pub(crate) fn solve_timed<I, T, G, E>(
+    (inst, parse): (Result<I, E>, Duration),
+    part_one: impl FnOnce(&I) -> Result<T, E>,
+    part_two: impl FnOnce(&I) -> Result<G, E>,
+    extra: impl FnOnce(&I) -> Result<Vec<(String, Answer)>, E>,
+) -> Result<SolveReport<T, G>, E>
+where
+    T: Display + Serialize + PartialEq,
+    G: Display + Serialize + PartialEq,
+    E: From<Timeout>,
+{
+    let inst = inst?;
+    context::transition(Phase::PartOne)?;
+    let (p1, part_one_time) = timed(|| part_one(&inst));
+    let p1 = p1?;
+    context::transition(Phase::PartTwo)?;
+    let (p2, part_two_time) = timed(|| part_two(&inst));
+    let p2 = p2?;
+    context::transition(Phase::Extra)?;
+    let mut solution = Solution::new(p1, p2);
+    solution.extra = extra(&inst)?;
+    context::check()?;
+
+    Ok(SolveReport {
+        solution,
+        timings: Timings {
+            parse,
+            part_one: part_one_time,
+            part_two: part_two_time,
+            parallel: false,
+        },
+    })
+}
+
+fn nanos(duration: Duration) -> u64 {
+    duration.as_nanos() as u64
+}
@@ -0,0 +1,153 @@
+use std::{cell::RefCell, fmt::Display};
+
+use serde::{Serialize, Serializer, ser::SerializeMap};
+
+use crate::{Answer, SolveContext, context::Phase};
+
+/// A step of a solve, as emitted by a solver via [trace](crate::trace!).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    /// The phase of the current [SolveContext], if there is one.
+    pub phase: Option<Phase>,
+    pub step: &'static str,
+    pub fields: Vec<(&'static str, Answer)>,
+}
+
+/// Displayed as the step followed by its fields, e.g. `split row=3 col=7`.
+impl Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.step)?;
+
+        for (name, value) in &self.fields {
+            write!(f, " {}={}", name, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Serialized as a flat object, so that a trace can be written as JSON lines.
+impl Serialize for Event {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.fields.len() + 2))?;
+        map.serialize_entry("phase", &self.phase.map(Phase::name))?;
+        map.serialize_entry("step", self.step)?;
+
+        for (name, value) in &self.fields {
+            map.serialize_entry(name, value)?;
+        }
+
+        map.end()
+    }
+}
+
+type Tracer = Box<dyn FnMut(Event)>;
+
+thread_local! {
+    static TRACER: RefCell<Option<Tracer>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with `tracer` receiving every [Event] emitted on this thread.
+///
+/// Events are only emitted if aoc-plumbing is built with the `trace`
+/// feature. Otherwise [trace](crate::trace!) compiles to nothing, and
+/// `tracer` is never called.
+///
+/// # Usage
+///
+/// ```
+/// use std::{cell::RefCell, rc::Rc};
+/// use aoc_plumbing::trace;
+///
+/// fn fib(n: u64) -> u64 {
+///     let (mut a, mut b) = (0, 1);
+///     for i in 0..n {
+///         trace!("step", i = i, value = a);
+///         (a, b) = (b, a + b);
+///     }
+///     a
+/// }
+///
+/// let events = Rc::new(RefCell::new(Vec::default()));
+/// let sink = events.clone();
+/// let result = trace::run(move |event| sink.borrow_mut().push(event.to_string()), || fib(3));
+///
+/// assert_eq!(result, 2);
+/// if cfg!(feature = "trace") {
+///     assert_eq!(*events.borrow(), ["step i=0 value=0", "step i=1 value=1", "step i=2 value=1"]);
+/// } else {
+///     assert!(events.borrow().is_empty());
+/// }
+/// ```
+pub fn run<R>(tracer: impl FnMut(Event) + 'static, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Tracer>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            TRACER.with_borrow_mut(|current| *current = self.0.take());
+        }
+    }
+
+    let _restore = Restore(TRACER.replace(Some(Box::new(tracer))));
+    f()
+}
+
+/// Whether a tracer is installed for this thread. This is what keeps the
+/// fields of an event from being evaluated when nobody is listening.
+#[doc(hidden)]
+pub fn enabled() -> bool {
+    TRACER.with_borrow(Option::is_some)
+}
+
+#[doc(hidden)]
+pub fn emit(step: &'static str, fields: Vec<(&'static str, Answer)>) {
+    let event = Event {
+        phase: SolveContext::current().map(|ctx| ctx.phase()),
+        step,
+        fields,
+    };
+
+    // a tracer that itself emits events would otherwise borrow twice
+    TRACER.with(|current| {
+        if let Ok(mut current) = current.try_borrow_mut()
+            && let Some(tracer) = current.as_mut()
+        {
+            tracer(event);
+        }
+    });
+}
+
+/// Emits a step of a solve to the tracer installed via [run], along with
+/// named fields that convert into [Answer]s:
+///
+/// ```ignore
+/// aoc_plumbing::trace!("split", row = i, col = j, timelines = count.clone());
+/// ```
+///
+/// The fields are only evaluated if a tracer is installed. Without the
+/// `trace` feature, this expands to nothing at all.
+#[cfg(feature = "trace")]
+#[macro_export]
+macro_rules! trace {
+    ($step:literal $(, $name:ident = $value:expr)* $(,)?) => {
+        if $crate::trace::enabled() {
+            $crate::trace::emit(
+                $step,
+                ::std::vec![$((stringify!($name), $crate::Answer::from($value))),*],
+            );
+        }
+    };
+}
+
+/// See the version of this macro with the `trace` feature enabled.
+#[cfg(not(feature = "trace"))]
+#[macro_export]
+macro_rules! trace {
+    ($step:literal $(, $name:ident = $value:expr)* $(,)?) => {
+        // keeps the fields type checked, and their variables used, without
+        // evaluating them
+        if false {
+            $(let _ = $crate::Answer::from($value);)*
+        }
+    };
+}
@@ -0,0 +1,91 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+/// How serious a [Diagnostic] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The input can be solved, but probably not to what was intended.
+    Warning,
+    /// The input cannot be solved, or solving it would fail.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Something wrong with an input that parsed fine, as found by
+/// [Problem::validate](crate::Problem::validate).
+///
+/// # Usage
+///
+/// ```
+/// use aoc_plumbing::validate::{Diagnostic, Severity};
+///
+/// let diagnostic = Diagnostic::error("light 3 is lit, but no button toggles it").on_line(2);
+///
+/// assert_eq!(diagnostic.severity, Severity::Error);
+/// assert_eq!(
+///     diagnostic.to_string(),
+///     "error: line 2: light 3 is lit, but no button toggles it"
+/// );
+/// assert_eq!(
+///     serde_json::to_string(&Diagnostic::warning("no junction boxes")).unwrap(),
+///     "{\"severity\":\"warning\",\"message\":\"no junction boxes\"}"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// The 1-based line number the diagnostic is about, if it is about one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn warning(message: impl Display) -> Self {
+        Self {
+            severity: Severity::Warning,
+            line: None,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn error(message: impl Display) -> Self {
+        Self {
+            severity: Severity::Error,
+            line: None,
+            message: message.to_string(),
+        }
+    }
+
+    /// Attributes the diagnostic to the given 1-based line.
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.severity)?;
+
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+
+        write!(f, "{}", self.message)
+    }
+}
@@ -0,0 +1,523 @@
+use std::{
+    fmt::{Display, Write},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{BorrowedFamily, BorrowedProblem, Params, Problem};
+
+/// An RGB color.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const BLACK: Self = Self::rgb(0, 0, 0);
+    pub const WHITE: Self = Self::rgb(255, 255, 255);
+    pub const GRAY: Self = Self::rgb(96, 96, 96);
+    pub const RED: Self = Self::rgb(220, 50, 47);
+    pub const GREEN: Self = Self::rgb(133, 153, 0);
+    pub const YELLOW: Self = Self::rgb(235, 185, 0);
+    pub const BLUE: Self = Self::rgb(38, 139, 210);
+    pub const MAGENTA: Self = Self::rgb(211, 54, 130);
+    pub const CYAN: Self = Self::rgb(42, 161, 152);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// The color `t` of the way from `self` to `other`, where `t` is clamped
+    /// to `0..=1`.
+    pub fn lerp(self, other: Self, t: f64) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Self::rgb(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+        )
+    }
+}
+
+/// Formatted as a hex color, e.g. `#ff8000`.
+impl Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// Something drawn on a [Scene], in scene units.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Rect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        color: Color,
+    },
+    Circle {
+        x: f64,
+        y: f64,
+        radius: f64,
+        color: Color,
+    },
+    Line {
+        from: (f64, f64),
+        to: (f64, f64),
+        color: Color,
+    },
+}
+
+/// A drawing that can be rendered by any of the backends in [Format].
+///
+/// Shapes are drawn in the order they were added, with `y` pointing down.
+/// Grids are usually drawn with one unit per cell, which the raster backends
+/// then scale up or down as needed.
+///
+/// # Usage
+///
+/// ```
+/// use aoc_plumbing::visualize::{Color, Format, Scene};
+///
+/// let mut scene = Scene::new(2.0, 1.0);
+/// scene.cell(1, 0, Color::RED);
+///
+/// let svg = String::from_utf8(scene.render(Format::Svg).unwrap()).unwrap();
+/// assert!(svg.contains(r##"<rect x="1" y="0" width="1" height="1" fill="#dc322f"/>"##));
+///
+/// let ppm = scene.render(Format::Ppm).unwrap();
+/// assert!(ppm.starts_with(b"P6\n"));
+///
+/// let ansi = String::from_utf8(scene.render(Format::Ansi).unwrap()).unwrap();
+/// assert_eq!(ansi.lines().count(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scene {
+    pub width: f64,
+    pub height: f64,
+    pub background: Color,
+    pub shapes: Vec<Shape>,
+}
+
+/// The longest side of raster images, in pixels.
+const IMAGE_SIZE: f64 = 1024.0;
+
+/// The widest that terminal renderings get, in characters.
+const TERMINAL_WIDTH: f64 = 160.0;
+
+impl Scene {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            background: Color::BLACK,
+            shapes: Vec::default(),
+        }
+    }
+
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: Color) -> &mut Self {
+        self.shapes.push(Shape::Rect {
+            x,
+            y,
+            width,
+            height,
+            color,
+        });
+        self
+    }
+
+    /// Fills the unit square at column `x` and row `y` of a grid.
+    pub fn cell(&mut self, x: usize, y: usize, color: Color) -> &mut Self {
+        self.rect(x as f64, y as f64, 1.0, 1.0, color)
+    }
+
+    pub fn circle(&mut self, x: f64, y: f64, radius: f64, color: Color) -> &mut Self {
+        self.shapes.push(Shape::Circle {
+            x,
+            y,
+            radius,
+            color,
+        });
+        self
+    }
+
+    pub fn line(&mut self, from: (f64, f64), to: (f64, f64), color: Color) -> &mut Self {
+        self.shapes.push(Shape::Line { from, to, color });
+        self
+    }
+
+    pub fn render(&self, format: Format) -> Result<Vec<u8>> {
+        match format {
+            Format::Ansi => Ok(self.to_ansi().into_bytes()),
+            Format::Svg => Ok(self.to_svg().into_bytes()),
+            Format::Ppm => Ok(self.to_ppm()),
+            Format::Png => self.to_png(),
+        }
+    }
+
+    /// Renders with truecolor escapes, packing two rows of pixels into each
+    /// line with half blocks.
+    pub fn to_ansi(&self) -> String {
+        let raster = self.rasterize((TERMINAL_WIDTH / self.width).min(1.0));
+        let mut ret = String::default();
+
+        for y in (0..raster.height).step_by(2) {
+            for x in 0..raster.width {
+                let top = raster.get(x, y);
+                let bottom = match y + 1 < raster.height {
+                    true => raster.get(x, y + 1),
+                    false => self.background,
+                };
+                let _ = write!(
+                    ret,
+                    "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m\u{2580}",
+                    top.r, top.g, top.b, bottom.r, bottom.g, bottom.b
+                );
+            }
+            ret.push_str("\x1b[0m\n");
+        }
+
+        ret
+    }
+
+    pub fn to_svg(&self) -> String {
+        let scale = self.image_scale();
+        let mut ret = format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {} {}" "#,
+                r#"width="{}" height="{}" shape-rendering="crispEdges">"#,
+                "\n",
+                r#"<rect width="100%" height="100%" fill="{}"/>"#,
+                "\n"
+            ),
+            self.width,
+            self.height,
+            (self.width * scale).round(),
+            (self.height * scale).round(),
+            self.background
+        );
+
+        for shape in &self.shapes {
+            let _ = match *shape {
+                Shape::Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                    color,
+                } => writeln!(
+                    ret,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                    x, y, width, height, color
+                ),
+                Shape::Circle {
+                    x,
+                    y,
+                    radius,
+                    color,
+                } => writeln!(
+                    ret,
+                    r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+                    x, y, radius, color
+                ),
+                Shape::Line { from, to, color } => writeln!(
+                    ret,
+                    r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="{}"/>"#,
+                    from.0,
+                    from.1,
+                    to.0,
+                    to.1,
+                    color,
+                    1.0 / scale
+                ),
+            };
+        }
+
+        ret.push_str("</svg>\n");
+        ret
+    }
+
+    /// Renders a binary PPM, which is trivial to write but large.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let raster = self.rasterize(self.image_scale());
+        let mut ret = format!("P6\n{} {}\n255\n", raster.width, raster.height).into_bytes();
+        ret.extend(raster.bytes());
+        ret
+    }
+
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        let raster = self.rasterize(self.image_scale());
+        let mut ret = Vec::default();
+
+        let mut encoder = png::Encoder::new(&mut ret, raster.width as u32, raster.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().context("Could not encode png")?;
+        writer
+            .write_image_data(&raster.bytes())
+            .context("Could not encode png")?;
+        writer.finish().context("Could not encode png")?;
+
+        Ok(ret)
+    }
+
+    /// Pixels per unit for images, so that the longest side is about
+    /// [IMAGE_SIZE] pixels. Small scenes are scaled up by a whole number, so
+    /// that grid cells stay the same size.
+    fn image_scale(&self) -> f64 {
+        let scale = IMAGE_SIZE / self.width.max(self.height);
+        match scale >= 1.0 {
+            true => scale.floor(),
+            false => scale,
+        }
+    }
+
+    fn rasterize(&self, scale: f64) -> Raster {
+        let mut raster = Raster::new(
+            ((self.width * scale).ceil() as usize).max(1),
+            ((self.height * scale).ceil() as usize).max(1),
+            self.background,
+        );
+
+        for shape in &self.shapes {
+            match *shape {
+                Shape::Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                    color,
+                } => {
+                    // always cover at least one pixel, so that small cells
+                    // do not disappear when scaled down
+                    let x0 = (x * scale).floor();
+                    let y0 = (y * scale).floor();
+                    let x1 = ((x + width) * scale).floor().max(x0 + 1.0);
+                    let y1 = ((y + height) * scale).floor().max(y0 + 1.0);
+                    raster.fill(x0, y0, x1, y1, |_, _| true, color);
+                }
+                Shape::Circle {
+                    x,
+                    y,
+                    radius,
+                    color,
+                } => {
+                    let (cx, cy, r) = (x * scale, y * scale, (radius * scale).max(0.5));
+                    raster.fill(
+                        (cx - r).floor(),
+                        (cy - r).floor(),
+                        (cx + r).ceil(),
+                        (cy + r).ceil(),
+                        |px, py| (px + 0.5 - cx).powi(2) + (py + 0.5 - cy).powi(2) <= r * r,
+                        color,
+                    );
+                }
+                Shape::Line { from, to, color } => {
+                    let (x0, y0) = (from.0 * scale, from.1 * scale);
+                    let (x1, y1) = (to.0 * scale, to.1 * scale);
+                    let steps = (x1 - x0).abs().max((y1 - y0).abs()).ceil().max(1.0);
+                    for i in 0..=steps as usize {
+                        let t = i as f64 / steps;
+                        raster.set(x0 + (x1 - x0) * t, y0 + (y1 - y0) * t, color);
+                    }
+                }
+            }
+        }
+
+        raster
+    }
+}
+
+struct Raster {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl Raster {
+    fn new(width: usize, height: usize, background: Color) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    fn get(&self, x: usize, y: usize) -> Color {
+        self.pixels[y * self.width + x]
+    }
+
+    fn set(&mut self, x: f64, y: f64, color: Color) {
+        if x >= 0.0 && y >= 0.0 && (x as usize) < self.width && (y as usize) < self.height {
+            self.pixels[y as usize * self.width + x as usize] = color;
+        }
+    }
+
+    /// Sets the pixels within the bounds for which `inside` holds.
+    fn fill(
+        &mut self,
+        x0: f64,
+        y0: f64,
+        x1: f64,
+        y1: f64,
+        inside: impl Fn(f64, f64) -> bool,
+        color: Color,
+    ) {
+        let clamp = |v: f64, max: usize| v.clamp(0.0, max as f64) as usize;
+
+        for y in clamp(y0, self.height)..clamp(y1, self.height) {
+            for x in clamp(x0, self.width)..clamp(x1, self.width) {
+                if inside(x as f64, y as f64) {
+                    self.pixels[y * self.width + x] = color;
+                }
+            }
+        }
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        self.pixels.iter().flat_map(|c| [c.r, c.g, c.b]).collect()
+    }
+}
+
+/// The backends a [Scene] can be rendered with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Colored half blocks for a truecolor terminal.
+    #[default]
+    Ansi,
+    Svg,
+    Ppm,
+    Png,
+}
+
+impl Format {
+    pub const ALL: [Self; 4] = [Self::Ansi, Self::Svg, Self::Ppm, Self::Png];
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ansi => write!(f, "ansi"),
+            Self::Svg => write!(f, "svg"),
+            Self::Ppm => write!(f, "ppm"),
+            Self::Png => write!(f, "png"),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.to_string() == s)
+            .ok_or_else(|| {
+                anyhow!(
+                    "unknown format '{}', expected one of: ansi, svg, ppm, png",
+                    s
+                )
+            })
+    }
+}
+
+/// A problem that can draw its parsed input, and possibly how it is solved.
+///
+/// Visualizations are registered alongside the problem via
+/// [register_visualizer](crate::register_visualizer), and can then be
+/// rendered from the cli with `aoc visualize`.
+pub trait Visualize {
+    fn visualize(&self) -> Scene;
+}
+
+/// An object-safe facade over [Visualize], which parses the input before
+/// drawing it.
+pub trait DynVisualize: Send + Sync {
+    fn year(&self) -> usize;
+    fn day(&self) -> usize;
+
+    /// Parses `raw_input`, with the given overrides applied to the default
+    /// config, and draws it.
+    fn visualize(&self, raw_input: &str, params: &Params) -> Result<Scene>;
+}
+
+/// Erases the [Problem] `P` into a boxed [DynVisualize].
+pub fn erase_visualizer<P>() -> Box<dyn DynVisualize>
+where
+    P: Problem + Visualize + 'static,
+    <P as Problem>::ProblemError: Into<anyhow::Error>,
+{
+    Box::new(Erased::<P>(PhantomData))
+}
+
+/// Erases the [BorrowedProblem] named by `F` into a boxed [DynVisualize].
+pub fn erase_borrowed_visualizer<F>() -> Box<dyn DynVisualize>
+where
+    F: BorrowedFamily,
+    for<'a> F::Problem<'a>: Visualize,
+{
+    Box::new(ErasedBorrowed::<F>(PhantomData))
+}
+
+struct Erased<P>(PhantomData<fn() -> P>);
+
+impl<P> DynVisualize for Erased<P>
+where
+    P: Problem + Visualize + 'static,
+    <P as Problem>::ProblemError: Into<anyhow::Error>,
+{
+    fn year(&self) -> usize {
+        P::YEAR
+    }
+
+    fn day(&self) -> usize {
+        P::DAY
+    }
+
+    fn visualize(&self, raw_input: &str, params: &Params) -> Result<Scene> {
+        let input = P::NORMALIZE.apply(raw_input);
+        let inst = P::instance_with(&input, config(params, P::DAY)?)
+            .map_err(Into::<P::ProblemError>::into)
+            .map_err(Into::into)?;
+        Ok(inst.visualize())
+    }
+}
+
+struct ErasedBorrowed<F>(PhantomData<fn() -> F>);
+
+impl<F> DynVisualize for ErasedBorrowed<F>
+where
+    F: BorrowedFamily,
+    for<'a> F::Problem<'a>: Visualize,
+{
+    fn year(&self) -> usize {
+        <F::Problem<'static> as BorrowedProblem<'static>>::YEAR
+    }
+
+    fn day(&self) -> usize {
+        <F::Problem<'static> as BorrowedProblem<'static>>::DAY
+    }
+
+    fn visualize(&self, raw_input: &str, params: &Params) -> Result<Scene> {
+        let input = <F::Problem<'static> as BorrowedProblem<'static>>::NORMALIZE.apply(raw_input);
+        let config = config(params, self.day())?;
+        let inst = F::Problem::parse_with(&input, config).map_err(Into::into)?;
+        Ok(inst.visualize())
+    }
+}
+
+fn config<C>(params: &Params, day: usize) -> Result<C>
+where
+    C: Default + Serialize + DeserializeOwned,
+{
+    params
+        .apply(C::default())
+        .with_context(|| format!("Invalid parameters for day {}", day))
+}
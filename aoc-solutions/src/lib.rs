@@ -0,0 +1,95 @@
+//! Each day registers itself with [aoc_plumbing::register_day], but a crate is
+//! only linked into a binary if something refers to it. Depending on this
+//! crate and calling [registry] ensures that every day is linked.
+//!
+//! A day is linked by two entries, a dependency in the `Cargo.toml` of this
+//! crate and a `pub use` in the sorted block below. `scripts/new.sh` adds both
+//! for a new day, and the tests check that every dependency has its `pub use`.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use aoc_plumbing::Registry;
+
+/// The year of the days at the root of the workspace, as given by the `YEAR`
+/// file at its root. Days of any other year live in a directory named after
+/// their year, e.g. `2024/day-001-*`.
+pub const WORKSPACE_YEAR: usize =
+    match usize::from_str_radix(include_str!("../../YEAR").trim_ascii(), 10) {
+        Ok(year) => year,
+        Err(_) => panic!("the YEAR file must contain just the year of the workspace"),
+    };
+
+/// The registry of every day's solution.
+pub fn registry() -> &'static Registry {
+    Registry::global()
+}
+
+/// Finds the `day-NNN-*` directory of the given day within `root`, the root
+/// of the workspace.
+pub fn day_dir(root: &Path, year: usize, day: usize) -> Option<PathBuf> {
+    let year_dir = match year {
+        WORKSPACE_YEAR => root.to_path_buf(),
+        _ => root.join(year.to_string()),
+    };
+    let prefix = format!("day-{:03}-", day);
+
+    fs::read_dir(year_dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .find(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+}
+
+/// Finds the real input of the given day, `input.txt` in its
+/// [directory](day_dir).
+pub fn input_path(root: &Path, year: usize, day: usize) -> Option<PathBuf> {
+    day_dir(root, year, day)
+        .map(|dir| dir.join("input.txt"))
+        .filter(|path| path.is_file())
+}
+
+/// Finds the `n`th example of the given day in its [directory](day_dir),
+/// `example.txt` for the first and `example{n}.txt` for any other.
+pub fn example_path(root: &Path, year: usize, day: usize, n: usize) -> Option<PathBuf> {
+    let name = match n {
+        1 => "example.txt".to_string(),
+        _ => format!("example{}.txt", n),
+    };
+
+    day_dir(root, year, day)
+        .map(|dir| dir.join(name))
+        .filter(|path| path.is_file())
+}
+
+pub use cafeteria;
+pub use factory;
+pub use gift_shop;
+pub use laboratories;
+pub use lobby;
+pub use playground;
+pub use printing_department;
+pub use reactor;
+pub use secret_entrance;
+pub use trash_compactor;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_day_is_linked() {
+        let days = include_str!("../Cargo.toml")
+            .lines()
+            .filter(|line| line.contains("/day-"))
+            .count();
+
+        assert_eq!(
+            registry().len(),
+            days,
+            "every day in Cargo.toml needs a `pub use` in src/lib.rs"
+        );
+    }
+}
@@ -11,9 +11,24 @@ if [ -z ${AOC_DAY+x} ]; then
     exit 1
 fi
 
+# The days of the workspace's own year live at its root, while those of any
+# other year live in a directory named after that year. AOC_YEAR defaults to
+# the workspace's own year, as given by the YEAR file at its root.
+workspace_year=$(cat "$(dirname "$0")/../../YEAR")
+year=${AOC_YEAR:-$workspace_year}
+
+if [ "$year" = "$workspace_year" ]; then
+    year_dir="."
+else
+    year_dir="./${year}"
+fi
+
 # We need to zero-pad the day to 3 digits to properly match our inputs.
 padded=$(printf "%03d" "$AOC_DAY")
-search=$(find . -type d -name "day-${padded}*" -print -quit)
+search=""
+if [ -d "$year_dir" ]; then
+    search=$(find "$year_dir" -mindepth 1 -maxdepth 1 -type d -name "day-${padded}*" -print -quit)
+fi
 
 # The specification says that if an input does not exist for a given day, we
 # need to exit with a nonzero code.
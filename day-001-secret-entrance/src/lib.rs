@@ -1,85 +1,235 @@
-use std::str::FromStr;
+use std::io::BufRead;
 
-use aoc_plumbing::Problem;
+use aoc_plumbing::{
+    BorrowedProblem, Example, ParseError, generate::Generate, parse::for_each_line,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
-pub struct SecretEntrance {
-    instructions: Vec<i32>,
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SecretEntranceConfig {
+    /// The number of positions on the dial.
+    pub positions: i32,
+    /// The position the dial starts at.
+    pub start: i32,
 }
 
-impl FromStr for SecretEntrance {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let instructions = s
-            .lines()
-            .map(|line| {
-                let (left, right) = line.split_at(1);
-                if left == "L" {
-                    right.parse::<i32>().map(|x| -x)
-                } else {
-                    right.parse::<i32>()
-                }
-            })
-            .collect::<Result<_, _>>()?;
-
-        Ok(Self { instructions })
+impl Default for SecretEntranceConfig {
+    fn default() -> Self {
+        Self {
+            positions: 100,
+            start: 50,
+        }
     }
 }
 
-impl SecretEntrance {
-    fn password(&self) -> usize {
-        let mut ret = 0;
-        let mut cur = 50;
+/// Tracks the dial through a sequence of rotations, counting the passwords
+/// for both parts at once.
+#[derive(Debug, Clone, Copy)]
+struct Dial {
+    positions: i32,
+    cur: i32,
+    /// The number of times a rotation ended at 0.
+    stops: usize,
+    /// The number of times the dial pointed at 0 at all.
+    clicks: usize,
+}
 
-        for num in &self.instructions {
-            cur = (cur + num).rem_euclid(100);
-            if cur == 0 {
-                ret += 1;
+impl Dial {
+    /// A dial set up as described by `config`, which comes from the user and
+    /// so is checked to describe a dial that can actually be turned.
+    fn new(config: SecretEntranceConfig) -> Result<Self, ParseError> {
+        let SecretEntranceConfig { positions, start } = config;
+        if positions <= 0 {
+            return Err(ParseError::new(format!(
+                "the dial needs at least one position, got {}",
+                positions
+            )));
+        }
+        if !(0..positions).contains(&start) {
+            return Err(ParseError::new(format!(
+                "the dial cannot start at {}, as its positions are 0 to {}",
+                start,
+                positions - 1
+            )));
+        }
+
+        Ok(Self {
+            positions,
+            cur: start,
+            stops: 0,
+            clicks: 0,
+        })
+    }
+
+    fn rotate(&mut self, num: i32) {
+        let mut cur = self.cur;
+        if cur == 0 && num < 0 {
+            cur = self.positions;
+        }
+        cur += num;
+        let clicks = self.clicks;
+        self.clicks += cur.div_euclid(self.positions).unsigned_abs() as usize;
+        cur = cur.rem_euclid(self.positions);
+        if cur == 0 {
+            self.stops += 1;
+            if num < 0 {
+                self.clicks += 1;
             }
         }
+        self.cur = cur;
+
+        aoc_plumbing::trace!("rotate", distance = num, position = cur);
+        if self.clicks > clicks {
+            aoc_plumbing::trace!("zero", hits = self.clicks - clicks);
+        }
+    }
+}
+
+/// Parses the signed distance of a rotation, where left is negative. `line`
+/// must be a subslice of `input`.
+fn parse_rotation(input: &str, line: &str) -> Result<i32, ParseError> {
+    let Some(right) = line.strip_prefix(['L', 'R']) else {
+        let direction = line.get(..1).unwrap_or(line);
+        return Err(ParseError::spanning(
+            input,
+            direction,
+            "expected rotation to start with 'L' or 'R'",
+        ));
+    };
+
+    let distance = right.parse::<i32>().map_err(|e| {
+        ParseError::spanning(input, right, format!("invalid rotation distance: {}", e))
+    })?;
 
-        ret
+    if line.starts_with('L') {
+        Ok(-distance)
+    } else {
+        Ok(distance)
     }
+}
 
-    fn new_password(&self) -> usize {
-        let mut ret = 0;
-        let mut cur = 50;
+/// The rotations are decoded from the input on demand, rather than being
+/// parsed up front. When streamed, the dial is instead turned while reading.
+#[derive(Debug, Clone)]
+pub struct SecretEntrance<'a> {
+    input: &'a str,
+    /// The dial before any rotation.
+    start: Dial,
+    streamed: Option<Dial>,
+}
 
-        for num in &self.instructions {
-            if cur == 0 && *num < 0 {
-                cur = 100;
-            }
-            cur += num;
-            ret += cur.div_euclid(100).unsigned_abs();
-            cur = cur.rem_euclid(100);
-            if cur == 0 && *num < 0 {
-                ret += 1;
-            }
+impl<'a> SecretEntrance<'a> {
+    fn dial(&self) -> Result<Dial, ParseError> {
+        if let Some(dial) = self.streamed {
+            return Ok(dial);
+        }
+
+        let mut dial = self.start;
+        for line in self.input.lines() {
+            dial.rotate(parse_rotation(self.input, line)?);
         }
 
-        ret as usize
+        Ok(dial)
     }
 }
 
-impl Problem for SecretEntrance {
+impl<'a> BorrowedProblem<'a> for SecretEntrance<'a> {
+    const YEAR: usize = 2025;
     const DAY: usize = 1;
     const TITLE: &'static str = "secret entrance";
     const README: &'static str = include_str!("../README.md");
 
+    type At<'b> = SecretEntrance<'b>;
+    type Config = SecretEntranceConfig;
     type ProblemError = anyhow::Error;
     type P1 = usize;
     type P2 = usize;
 
-    fn part_one(&mut self) -> Result<Self::P1, Self::ProblemError> {
-        Ok(self.password())
+    fn examples() -> Vec<Example> {
+        vec![
+            Example::file("example.txt").part_one(3).part_two(6),
+            Example::text("R5\nL3\nL12")
+                .param("positions", 10)
+                .param("start", 5)
+                .part_one(1)
+                .part_two(2),
+        ]
     }
 
-    fn part_two(&mut self) -> Result<Self::P2, Self::ProblemError> {
-        Ok(self.new_password())
+    fn parse_with(raw_input: &'a str, config: Self::Config) -> Result<Self, Self::ProblemError> {
+        Ok(Self {
+            input: raw_input,
+            start: Dial::new(config)?,
+            streamed: None,
+        })
     }
+
+    fn from_reader(
+        reader: impl BufRead,
+        config: Self::Config,
+    ) -> Option<Result<Self, Self::ProblemError>> {
+        let start = match Dial::new(config) {
+            Ok(dial) => dial,
+            Err(e) => return Some(Err(e.into())),
+        };
+        let mut dial = start;
+        let result = for_each_line(reader, |i, line| {
+            dial.rotate(parse_rotation(line, line).map_err(|e| e.on_line(i))?);
+            Ok::<_, Self::ProblemError>(())
+        });
+
+        Some(result.map(|()| Self {
+            input: "",
+            start,
+            streamed: Some(dial),
+        }))
+    }
+
+    fn part_one(&self) -> Result<Self::P1, Self::ProblemError> {
+        Ok(self.dial()?.stops)
+    }
+
+    fn part_two(&self) -> Result<Self::P2, Self::ProblemError> {
+        Ok(self.dial()?.clicks)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SecretEntranceSize {
+    /// The number of rotations.
+    pub rotations: usize,
+    /// The furthest a single rotation can turn the dial.
+    pub max_distance: u32,
 }
 
+impl Default for SecretEntranceSize {
+    fn default() -> Self {
+        Self {
+            rotations: 4659,
+            max_distance: 999,
+        }
+    }
+}
+
+impl Generate for SecretEntrance<'_> {
+    type Size = SecretEntranceSize;
+
+    fn generate(rng: &mut impl Rng, size: &Self::Size) -> String {
+        (0..size.rotations)
+            .map(|_| {
+                let direction = if rng.random_bool(0.5) { 'L' } else { 'R' };
+                format!("{}{}", direction, rng.random_range(1..=size.max_distance))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+aoc_plumbing::register_day!(SecretEntrance<'_>);
+aoc_plumbing::register_generator!(SecretEntrance<'_>);
+aoc_plumbing::example_tests!(SecretEntrance<'_>, full_dataset = (1158, 6860));
+
 #[cfg(test)]
 mod tests {
     use aoc_plumbing::Solution;
@@ -87,17 +237,47 @@ mod tests {
     use super::*;
 
     #[test]
-    #[ignore]
-    fn full_dataset() {
-        let input = std::fs::read_to_string("input.txt").expect("Unable to load input");
-        let solution = SecretEntrance::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(1158, 6860));
+    fn streamed() {
+        let input = std::fs::File::open("example.txt").expect("Unable to load input");
+        let reader = std::io::BufReader::new(input);
+        let report = SecretEntrance::solve_reader(reader, SecretEntranceConfig::default());
+        assert_eq!(report.unwrap().unwrap().solution, Solution::new(3, 6));
+    }
+
+    #[test]
+    fn parse_error_location() {
+        let location = |input| {
+            let err = SecretEntrance::solve(input).unwrap_err();
+            let loc = err
+                .downcast_ref::<ParseError>()
+                .unwrap()
+                .location()
+                .unwrap();
+            (loc.line, loc.columns.clone())
+        };
+
+        assert_eq!(location("L68\nR30\nX5\nL1x"), (3, 0..1));
+        assert_eq!(location("L68\nR30\nL1x"), (3, 1..3));
+    }
+
+    #[test]
+    fn invalid_config() {
+        let solve = |positions, start| {
+            SecretEntrance::solve_with("R5", SecretEntranceConfig { positions, start })
+                .map_err(|e| e.downcast::<ParseError>().unwrap())
+        };
+
+        assert!(solve(0, 0).is_err());
+        assert!(solve(-10, 0).is_err());
+        assert!(solve(10, 10).is_err());
+        assert!(solve(10, -1).is_err());
+        assert_eq!(solve(1, 0).unwrap(), Solution::new(1, 5));
     }
 
     #[test]
-    fn example() {
+    fn normalized() {
         let input = std::fs::read_to_string("example.txt").expect("Unable to load input");
-        let solution = SecretEntrance::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(3, 6));
+        let input = format!("\u{feff}{}\r\n\r\n", input.replace('\n', "\r\n"));
+        assert_eq!(SecretEntrance::solve(&input).unwrap(), Solution::new(3, 6));
     }
 }
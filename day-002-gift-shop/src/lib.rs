@@ -1,14 +1,25 @@
 use std::str::FromStr;
 
-use anyhow::anyhow;
-use aoc_plumbing::Problem;
+use aoc_plumbing::{
+    Answer, Example, ParseError, Problem, generate::Generate, reference::Reference,
+};
+use rand::Rng;
 use rustc_hash::FxHashSet;
+use serde::{Deserialize, Serialize};
+
+/// The most digits an id can have. Invalid ids are found by repeating
+/// sequences of digits until they pass the start of a range, which can take
+/// them several digits longer than the id itself, and they must still fit in
+/// a `u64`.
+const MAX_DIGITS: u32 = 14;
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
 struct Range {
     start: u64,
+    /// The end of the range, which is exclusive.
     end: u64,
     start_length: u32,
+    /// The number of digits of the last id in the range.
     end_length: u32,
 }
 
@@ -18,7 +29,7 @@ impl Range {
             start,
             end,
             start_length: start.ilog10() + 1,
-            end_length: end.ilog10() + 1,
+            end_length: (end - 1).checked_ilog10().unwrap_or(0) + 1,
         }
     }
 }
@@ -29,17 +40,40 @@ pub struct GiftShop {
 }
 
 impl FromStr for GiftShop {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut ranges = Vec::default();
 
         for token in s.trim().split(',') {
-            let (s, e) = token
+            let (start_token, end_token) = token
                 .split_once('-')
-                .ok_or_else(|| anyhow!("Could not parse range"))?;
-            let start = s.parse()?;
-            let end = e.parse().map(|x: u64| x + 1)?;
+                .ok_or_else(|| ParseError::spanning(s, token, "could not parse range"))?;
+            let start = start_token.parse().map_err(|e| {
+                ParseError::spanning(s, start_token, format!("invalid range start: {}", e))
+            })?;
+            if start == 0 {
+                return Err(ParseError::spanning(
+                    s,
+                    start_token,
+                    "expected a range start above 0",
+                ));
+            }
+
+            let end = end_token
+                .parse::<u64>()
+                .map_err(|e| {
+                    ParseError::spanning(s, end_token, format!("invalid range end: {}", e))
+                })?
+                .checked_add(1)
+                .filter(|&end| end <= 10u64.pow(MAX_DIGITS))
+                .ok_or_else(|| {
+                    ParseError::spanning(
+                        s,
+                        end_token,
+                        format!("expected ids of at most {} digits", MAX_DIGITS),
+                    )
+                })?;
             ranges.push(Range::new(start, end));
         }
 
@@ -123,7 +157,7 @@ impl GiftShop {
             cur_gap = cur_gap * mult + 1;
         }
 
-        while cur <= ceil {
+        while cur < ceil {
             ids.insert(cur);
             cur += cur_gap;
         }
@@ -131,41 +165,168 @@ impl GiftShop {
 }
 
 impl Problem for GiftShop {
+    const YEAR: usize = 2025;
     const DAY: usize = 2;
     const TITLE: &'static str = "gift shop";
     const README: &'static str = include_str!("../README.md");
 
+    type Config = ();
     type ProblemError = anyhow::Error;
     type P1 = u64;
     type P2 = u64;
 
-    fn part_one(&mut self) -> Result<Self::P1, Self::ProblemError> {
+    fn examples() -> Vec<Example> {
+        vec![
+            Example::file("example.txt")
+                .part_one(1227775554)
+                .part_two(4174379265_u64),
+        ]
+    }
+
+    fn part_one(&self) -> Result<Self::P1, Self::ProblemError> {
         Ok(self.invalid_ids())
     }
 
-    fn part_two(&mut self) -> Result<Self::P2, Self::ProblemError> {
+    fn part_two(&self) -> Result<Self::P2, Self::ProblemError> {
         Ok(self.more_invalid_ids())
     }
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GiftShopSize {
+    /// The number of ranges.
+    pub ranges: usize,
+    /// The most digits an id can have.
+    pub max_digits: u32,
+    /// The most ids a range can span.
+    pub max_width: u64,
+}
+
+impl Default for GiftShopSize {
+    fn default() -> Self {
+        Self {
+            ranges: 33,
+            max_digits: 10,
+            max_width: 1_000_000,
+        }
+    }
+}
+
+impl Generate for GiftShop {
+    type Size = GiftShopSize;
+
+    fn generate(rng: &mut impl Rng, size: &Self::Size) -> String {
+        let mut ranges: Vec<(u64, u64)> = Vec::default();
+
+        // give up eventually if the ranges can't fit without overlapping
+        for _ in 0..size.ranges * 100 {
+            if ranges.len() == size.ranges {
+                break;
+            }
+
+            let digits = rng.random_range(1..=size.max_digits);
+            let start = rng.random_range(10u64.pow(digits - 1)..10u64.pow(digits));
+            let end = start + rng.random_range(0..size.max_width);
+
+            // the ranges in the real input never overlap
+            if ranges.iter().all(|&(s, e)| end < s || start > e) {
+                ranges.push((start, end));
+            }
+        }
+
+        ranges
+            .iter()
+            .map(|(s, e)| format!("{}-{}", s, e))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// Checks every id in every range by comparing its digits as a string.
+impl Reference for GiftShop {
+    fn small() -> Self::Size {
+        GiftShopSize {
+            ranges: 4,
+            max_digits: 8,
+            max_width: 2000,
+        }
+    }
+
+    fn reference_part_one(input: &str) -> Result<Answer, anyhow::Error> {
+        reference_sum(input, |id| {
+            let (left, right) = id.split_at(id.len() / 2);
+            left == right
+        })
+    }
+
+    fn reference_part_two(input: &str) -> Result<Answer, anyhow::Error> {
+        reference_sum(input, |id| {
+            (1..=id.len() / 2).any(|l| id.len() % l == 0 && id[..l].repeat(id.len() / l) == id)
+        })
+    }
+}
+
+fn reference_sum(input: &str, invalid: impl Fn(&str) -> bool) -> Result<Answer, anyhow::Error> {
+    let mut ret = 0;
+
+    for range in input.trim().split(',') {
+        let (start, end) = range
+            .split_once('-')
+            .ok_or_else(|| anyhow::anyhow!("could not parse range"))?;
+        for id in start.parse::<u64>()?..=end.parse()? {
+            if invalid(&id.to_string()) {
+                ret += id;
+            }
+        }
+    }
+
+    Ok(ret.into())
+}
+
+aoc_plumbing::register_day!(GiftShop);
+aoc_plumbing::register_generator!(GiftShop);
+aoc_plumbing::example_tests!(GiftShop, full_dataset = (19605500130_u64, 36862281418_u64));
+aoc_plumbing::reference_tests!(GiftShop);
+
 #[cfg(test)]
 mod tests {
-    use aoc_plumbing::Solution;
-
     use super::*;
 
     #[test]
-    #[ignore]
-    fn full_dataset() {
-        let input = std::fs::read_to_string("input.txt").expect("Unable to load input");
-        let solution = GiftShop::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(19605500130, 36862281418));
+    fn start_of_zero() {
+        let err = GiftShop::from_str("11-22,0-5").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 7: expected a range start above 0"
+        );
+    }
+
+    #[test]
+    fn too_many_digits() {
+        let err = GiftShop::from_str("11-22,95-18446744073709551615").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 10: expected ids of at most 14 digits"
+        );
+
+        let err = GiftShop::from_str("100000000000000-100000000000100").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 17: expected ids of at most 14 digits"
+        );
     }
 
     #[test]
-    fn example() {
-        let input = std::fs::read_to_string("example.txt").expect("Unable to load input");
-        let solution = GiftShop::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(1227775554, 4174379265));
+    fn longest_ids() {
+        let input = "99999999999000-99999999999999";
+        let shop = GiftShop::from_str(input).unwrap();
+        assert_eq!(
+            Answer::from(shop.invalid_ids()),
+            GiftShop::reference_part_one(input).unwrap()
+        );
+        assert_eq!(
+            Answer::from(shop.more_invalid_ids()),
+            GiftShop::reference_part_two(input).unwrap()
+        );
     }
 }
@@ -1,10 +1,16 @@
-use std::str::FromStr;
+use std::{io::BufRead, str::FromStr};
 
 use anyhow::Result;
 use anyhow::anyhow;
-use aoc_plumbing::Problem;
+use aoc_plumbing::{
+    Answer, Example, ParseError, Problem, generate::Generate, parse::for_each_line,
+    reference::Reference,
+};
+use itertools::Itertools;
+use rand::Rng;
 use rayon::iter::IntoParallelRefIterator;
 use rayon::iter::ParallelIterator;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 struct BatteryBank {
@@ -43,62 +49,233 @@ impl BatteryBank {
     }
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LobbyConfig {
+    /// The number of batteries to turn on in each bank for part one.
+    pub small: usize,
+    /// The number of batteries to turn on in each bank for part two.
+    pub large: usize,
+}
+
+impl Default for LobbyConfig {
+    fn default() -> Self {
+        Self {
+            small: 2,
+            large: 12,
+        }
+    }
+}
+
+impl LobbyConfig {
+    /// The most batteries that can be turned on in a bank, as the joltage of
+    /// any more does not fit in a `u64`.
+    const MAX_BATTERIES: usize = 19;
+
+    /// Checks that the config, which comes from the user, only turns on as
+    /// many batteries as a joltage can hold.
+    fn check(self) -> Result<Self, ParseError> {
+        for (part, count) in [("small", self.small), ("large", self.large)] {
+            if count > Self::MAX_BATTERIES {
+                return Err(ParseError::new(format!(
+                    "at most {} batteries can be turned on in a bank, but {} is {}",
+                    Self::MAX_BATTERIES,
+                    part,
+                    count
+                )));
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+/// Adds the joltage of a bank to a total, failing rather than overflowing.
+fn add_joltage(total: u64, joltage: u64) -> Result<u64> {
+    total
+        .checked_add(joltage)
+        .ok_or_else(|| anyhow!("The total joltage does not fit in a u64"))
+}
+
+/// When streamed, the banks are not kept, and the totals for both parts are
+/// instead summed while reading.
 #[derive(Debug, Clone)]
 pub struct Lobby {
-    part1: u64,
-    part2: u64,
+    battery_banks: Vec<BatteryBank>,
+    config: LobbyConfig,
+    streamed: Option<(u64, u64)>,
 }
 
 impl FromStr for Lobby {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let battery_banks: Vec<_> = s
+        Self::parse(s, LobbyConfig::default())
+    }
+}
+
+impl Lobby {
+    fn parse(s: &str, config: LobbyConfig) -> Result<Self> {
+        let config = config.check()?;
+        let battery_banks = s
             .lines()
             .map(BatteryBank::from_str)
             .collect::<Result<_>>()?;
 
-        let sums = battery_banks
-            .par_iter()
-            .map(|x| x.joltages(12))
-            .map(|x| (x[2], x[12]))
-            .reduce(|| (0, 0), |(a, b), (c, d)| (a + c, b + d));
-
         Ok(Self {
-            part1: sums.0,
-            part2: sums.1,
+            battery_banks,
+            config,
+            streamed: None,
         })
     }
-}
 
-impl Lobby {
-    fn total_small_joltage(&self) -> u64 {
-        self.part1
+    fn total_joltage(&self, count: usize) -> Result<u64> {
+        self.battery_banks
+            .par_iter()
+            .map(|x| Ok(x.joltages(count)[count]))
+            .try_reduce(|| 0, add_joltage)
+    }
+
+    fn total_small_joltage(&self) -> Result<u64> {
+        match self.streamed {
+            Some((small, _)) => Ok(small),
+            None => self.total_joltage(self.config.small),
+        }
     }
 
-    fn total_large_joltage(&self) -> u64 {
-        self.part2
+    fn total_large_joltage(&self) -> Result<u64> {
+        match self.streamed {
+            Some((_, large)) => Ok(large),
+            None => self.total_joltage(self.config.large),
+        }
     }
 }
 
 impl Problem for Lobby {
+    const YEAR: usize = 2025;
     const DAY: usize = 3;
     const TITLE: &'static str = "lobby";
     const README: &'static str = include_str!("../README.md");
 
+    type Config = LobbyConfig;
     type ProblemError = anyhow::Error;
     type P1 = u64;
     type P2 = u64;
 
-    fn part_one(&mut self) -> Result<Self::P1, Self::ProblemError> {
-        Ok(self.total_small_joltage())
+    fn examples() -> Vec<Example> {
+        vec![
+            Example::file("example.txt")
+                .part_one(357)
+                .part_two(3121910778619_u64),
+        ]
+    }
+
+    fn instance_with(raw_input: &str, config: Self::Config) -> Result<Self> {
+        Self::parse(raw_input, config)
+    }
+
+    fn from_reader(reader: impl BufRead, config: Self::Config) -> Option<Result<Self>> {
+        let LobbyConfig { small, large } = match config.check() {
+            Ok(config) => config,
+            Err(e) => return Some(Err(e.into())),
+        };
+        let mut totals = (0, 0);
+        let result = for_each_line(reader, |_, l| {
+            let joltages = BatteryBank::from_str(l)?.joltages(small.max(large));
+            totals.0 = add_joltage(totals.0, joltages[small])?;
+            totals.1 = add_joltage(totals.1, joltages[large])?;
+            Ok(())
+        });
+
+        Some(result.map(|()| Self {
+            battery_banks: Vec::default(),
+            config,
+            streamed: Some(totals),
+        }))
     }
 
-    fn part_two(&mut self) -> Result<Self::P2, Self::ProblemError> {
-        Ok(self.total_large_joltage())
+    fn part_one(&self) -> Result<Self::P1, Self::ProblemError> {
+        self.total_small_joltage()
+    }
+
+    fn part_two(&self) -> Result<Self::P2, Self::ProblemError> {
+        self.total_large_joltage()
     }
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LobbySize {
+    /// The number of battery banks.
+    pub banks: usize,
+    /// The number of batteries in each bank.
+    pub length: usize,
+}
+
+impl Default for LobbySize {
+    fn default() -> Self {
+        Self {
+            banks: 200,
+            length: 100,
+        }
+    }
+}
+
+impl Generate for Lobby {
+    type Size = LobbySize;
+
+    fn generate(rng: &mut impl Rng, size: &Self::Size) -> String {
+        (0..size.banks)
+            .map(|_| {
+                (0..size.length)
+                    .map(|_| char::from(b'0' + rng.random_range(1..=9)))
+                    .collect::<String>()
+            })
+            .join("\n")
+    }
+}
+
+/// Tries every way of turning on the batteries in each bank.
+impl Reference for Lobby {
+    fn small() -> Self::Size {
+        LobbySize {
+            banks: 5,
+            length: 14,
+        }
+    }
+
+    fn reference_part_one(input: &str) -> Result<Answer> {
+        reference_joltage(input, LobbyConfig::default().small)
+    }
+
+    fn reference_part_two(input: &str) -> Result<Answer> {
+        reference_joltage(input, LobbyConfig::default().large)
+    }
+}
+
+fn reference_joltage(input: &str, count: usize) -> Result<Answer> {
+    let mut ret = 0;
+
+    for bank in input.lines() {
+        let digits = bank
+            .chars()
+            .map(|x| x.to_digit(10).map(u64::from))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| anyhow!("Could not parse battery bank"))?;
+        ret += digits
+            .into_iter()
+            .combinations(count)
+            .map(|x| x.into_iter().fold(0, |acc, d| acc * 10 + d))
+            .max()
+            .ok_or_else(|| anyhow!("Not enough batteries in {}", bank))?;
+    }
+
+    Ok(ret.into())
+}
+
+aoc_plumbing::register_day!(Lobby);
+aoc_plumbing::register_generator!(Lobby);
+aoc_plumbing::example_tests!(Lobby, full_dataset = (17493, 173685428989126_u64));
+aoc_plumbing::reference_tests!(Lobby);
+
 #[cfg(test)]
 mod tests {
     use aoc_plumbing::Solution;
@@ -106,17 +283,39 @@ mod tests {
     use super::*;
 
     #[test]
-    #[ignore]
-    fn full_dataset() {
-        let input = std::fs::read_to_string("input.txt").expect("Unable to load input");
-        let solution = Lobby::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(17493, 173685428989126));
+    fn streamed() {
+        let input = std::fs::File::open("example.txt").expect("Unable to load input");
+        let reader = std::io::BufReader::new(input);
+        let report = Lobby::solve_reader(reader, LobbyConfig::default());
+        assert_eq!(
+            report.unwrap().unwrap().solution,
+            Solution::new(357, 3121910778619)
+        );
+    }
+
+    #[test]
+    fn invalid_config() {
+        let solve = |small, large| {
+            Lobby::solve_with("987654321111111", LobbyConfig { small, large })
+                .map_err(|e| e.downcast::<ParseError>().unwrap())
+        };
+
+        assert!(solve(2, 20).is_err());
+        assert!(solve(20, 12).is_err());
+        assert_eq!(solve(1, 15).unwrap(), Solution::new(9, 987654321111111));
     }
 
     #[test]
-    fn example() {
-        let input = std::fs::read_to_string("example.txt").expect("Unable to load input");
-        let solution = Lobby::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(357, 3121910778619));
+    fn joltage_overflow() {
+        let bank = "9".repeat(19);
+        let input = format!("{}\n{}", bank, bank);
+        let config = LobbyConfig {
+            small: 2,
+            large: 19,
+        };
+
+        assert!(Lobby::solve_with(&input, config).is_err());
+        let reader = std::io::Cursor::new(input);
+        assert!(Lobby::solve_reader(reader, config).unwrap().is_err());
     }
 }
@@ -1,7 +1,13 @@
 use std::{collections::VecDeque, str::FromStr};
 
-use aoc_common::grid::Grid;
-use aoc_plumbing::Problem;
+use aoc_common::grid::{Coordinate, Grid};
+use aoc_plumbing::{
+    Example, Problem,
+    generate::Generate,
+    visualize::{Color, Scene, Visualize},
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 pub struct PrintingDepartment {
@@ -18,20 +24,22 @@ impl FromStr for PrintingDepartment {
 }
 
 impl PrintingDepartment {
+    fn is_accessible(&self, coord: Coordinate) -> bool {
+        self.grid[coord] == '@'
+            && coord
+                .neighbours()
+                .into_iter()
+                .filter(|&x| self.grid.get(x) == Some('@'))
+                .count()
+                < 4
+    }
+
     fn accessible_paper(&self) -> usize {
         let mut ret = 0;
 
         for i in 0..self.grid.n {
             for j in 0..self.grid.m {
-                let coord = (i, j).into();
-                if self.grid[coord] == '@'
-                    && coord
-                        .neighbours()
-                        .into_iter()
-                        .filter(|&x| self.grid.get(x) == Some('@'))
-                        .count()
-                        < 4
-                {
+                if self.is_accessible((i, j).into()) {
                     ret += 1;
                 }
             }
@@ -40,14 +48,17 @@ impl PrintingDepartment {
         ret
     }
 
-    fn repeated_accessible_paper(&mut self) -> usize {
+    /// Removes rolls from a copy of the grid, so that the instance is left
+    /// as parsed.
+    fn repeated_accessible_paper(&self) -> usize {
+        let mut grid = self.grid.clone();
         let mut ret = 0;
         let mut q = VecDeque::default();
 
-        for i in 0..self.grid.n {
-            for j in 0..self.grid.m {
+        for i in 0..grid.n {
+            for j in 0..grid.m {
                 let coord = (i, j).into();
-                if self.grid[coord] == '@' {
+                if grid[coord] == '@' {
                     q.push_back(coord);
                 }
             }
@@ -56,27 +67,27 @@ impl PrintingDepartment {
         while !q.is_empty() {
             let coord = q.pop_front().unwrap();
 
-            if !self.grid.is_in_bounds(coord) {
+            if !grid.is_in_bounds(coord) {
                 continue;
             }
 
-            if self.grid[coord] == '.' {
+            if grid[coord] == '.' {
                 continue;
             }
 
             let count = coord
                 .neighbours()
                 .into_iter()
-                .filter(|&x| self.grid.get(x) == Some('@'))
+                .filter(|&x| grid.get(x) == Some('@'))
                 .count();
 
             if count < 4 {
                 for nb in coord.neighbours() {
-                    if self.grid.get(nb) == Some('@') {
+                    if grid.get(nb) == Some('@') {
                         q.push_back(nb);
                     }
                 }
-                self.grid[coord] = '.';
+                grid[coord] = '.';
                 ret += 1;
             }
         }
@@ -86,41 +97,118 @@ impl PrintingDepartment {
 }
 
 impl Problem for PrintingDepartment {
+    const YEAR: usize = 2025;
     const DAY: usize = 4;
     const TITLE: &'static str = "printing department";
     const README: &'static str = include_str!("../README.md");
 
+    type Config = ();
     type ProblemError = anyhow::Error;
     type P1 = usize;
     type P2 = usize;
 
-    fn part_one(&mut self) -> Result<Self::P1, Self::ProblemError> {
+    fn examples() -> Vec<Example> {
+        vec![Example::file("example.txt").part_one(13).part_two(43)]
+    }
+
+    fn part_one(&self) -> Result<Self::P1, Self::ProblemError> {
         Ok(self.accessible_paper())
     }
 
-    fn part_two(&mut self) -> Result<Self::P2, Self::ProblemError> {
+    fn part_two(&self) -> Result<Self::P2, Self::ProblemError> {
         Ok(self.repeated_accessible_paper())
     }
 }
 
+/// Draws the rolls of paper, with the ones a forklift can reach right away
+/// highlighted.
+impl Visualize for PrintingDepartment {
+    fn visualize(&self) -> Scene {
+        let mut scene = Scene::new(self.grid.m as f64, self.grid.n as f64);
+
+        for i in 0..self.grid.n {
+            for j in 0..self.grid.m {
+                let coord = (i, j).into();
+                if self.is_accessible(coord) {
+                    scene.cell(j, i, Color::YELLOW);
+                } else if self.grid[coord] == '@' {
+                    scene.cell(j, i, Color::GRAY);
+                }
+            }
+        }
+
+        scene
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PrintingDepartmentSize {
+    pub width: usize,
+    pub height: usize,
+    /// The chance of each position having a roll of paper.
+    pub density: f64,
+}
+
+impl Default for PrintingDepartmentSize {
+    fn default() -> Self {
+        Self {
+            width: 138,
+            height: 138,
+            density: 0.65,
+        }
+    }
+}
+
+impl Generate for PrintingDepartment {
+    type Size = PrintingDepartmentSize;
+
+    fn generate(rng: &mut impl Rng, size: &Self::Size) -> String {
+        (0..size.height)
+            .map(|_| {
+                (0..size.width)
+                    .map(|_| match rng.random::<f64>() < size.density {
+                        true => '@',
+                        false => '.',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+aoc_plumbing::register_day!(PrintingDepartment);
+aoc_plumbing::register_generator!(PrintingDepartment);
+aoc_plumbing::register_visualizer!(PrintingDepartment);
+aoc_plumbing::example_tests!(PrintingDepartment, full_dataset = (1491, 8722));
+
 #[cfg(test)]
 mod tests {
-    use aoc_plumbing::Solution;
+    use aoc_plumbing::visualize::Shape;
 
     use super::*;
 
     #[test]
-    #[ignore]
-    fn full_dataset() {
-        let input = std::fs::read_to_string("input.txt").expect("Unable to load input");
-        let solution = PrintingDepartment::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(1491, 8722));
+    fn visualize() {
+        let input = std::fs::read_to_string("example.txt").expect("Unable to load input");
+        let scene = PrintingDepartment::instance(&input).unwrap().visualize();
+        let highlighted = scene
+            .shapes
+            .iter()
+            .filter(|shape| matches!(shape, Shape::Rect { color, .. } if *color == Color::YELLOW))
+            .count();
+
+        assert_eq!((scene.width, scene.height), (10.0, 10.0));
+        assert_eq!(highlighted, 13);
     }
 
     #[test]
-    fn example() {
+    fn parts_are_repeatable() {
         let input = std::fs::read_to_string("example.txt").expect("Unable to load input");
-        let solution = PrintingDepartment::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(13, 43));
+        let inst = PrintingDepartment::instance(&input).unwrap();
+
+        assert_eq!(inst.part_two().unwrap(), 43);
+        assert_eq!(inst.part_one().unwrap(), 13);
+        assert_eq!(inst.part_two().unwrap(), 43);
     }
 }
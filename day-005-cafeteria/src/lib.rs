@@ -1,47 +1,94 @@
-use std::{cmp::Ordering, str::FromStr};
+use std::{cmp::Ordering, io::BufRead};
 
-use anyhow::anyhow;
 use aoc_common::interval::Intervals;
-use aoc_plumbing::Problem;
+use aoc_plumbing::{
+    BorrowedProblem, Example, ImmutableParts, ParseError, generate::Generate, parse::for_each_line,
+};
+use rand::{Rng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+
+const MISSING_BLANK_LINE: &str = "expected a blank line between the ranges and the ingredients";
+
+/// Adds the range on `l` to `ranges`. `l` must be a subslice of `s`.
+fn parse_range(s: &str, l: &str, ranges: &mut Intervals) -> Result<(), ParseError> {
+    let (start, end) = l
+        .split_once('-')
+        .ok_or_else(|| ParseError::spanning(s, l, "could not parse range"))?;
+    let start = start
+        .parse()
+        .map_err(|e| ParseError::spanning(s, start, format!("invalid range start: {}", e)))?;
+    let end = end
+        .parse::<isize>()
+        .map_err(|e| ParseError::spanning(s, end, format!("invalid range end: {}", e)))?;
+    ranges.add((start, end + 1).into());
+    Ok(())
+}
+
+/// Parses the ingredient id on `x`, which must be a subslice of `s`.
+fn parse_ingredient(s: &str, x: &str) -> Result<isize, ParseError> {
+    x.parse::<isize>()
+        .map_err(|e| ParseError::spanning(s, x, format!("invalid ingredient id: {}", e)))
+}
 
+/// The ingredient ids are only needed for part one, so they are left in the
+/// input and parsed on demand. When streamed, the fresh ingredients are
+/// instead counted while reading.
 #[derive(Debug, Clone)]
-pub struct Cafeteria {
+pub struct Cafeteria<'a> {
+    input: &'a str,
     ranges: Intervals,
-    ingredients: Vec<isize>,
+    ingredients: &'a str,
+    streamed: Option<usize>,
 }
 
-impl FromStr for Cafeteria {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl<'a> Cafeteria<'a> {
+    fn parse(s: &'a str) -> Result<Self, ParseError> {
         let mut ranges = Intervals::default();
         let (part1, part2) = s
             .split_once("\n\n")
-            .ok_or_else(|| anyhow!("could not parse input"))?;
+            .ok_or_else(|| ParseError::new(MISSING_BLANK_LINE))?;
 
         for l in part1.lines() {
-            let (s, e) = l
-                .split_once('-')
-                .ok_or_else(|| anyhow!("could not parse range"))?;
-            ranges.add((s.parse()?, e.parse::<isize>()? + 1).into());
+            parse_range(s, l, &mut ranges)?;
         }
 
-        let ingredients = part2
-            .lines()
-            .map(|x| x.parse::<isize>())
-            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            input: s,
+            ranges,
+            ingredients: part2,
+            streamed: None,
+        })
+    }
+
+    fn from_lines(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut ranges = Intervals::default();
+        let mut fresh = None;
+
+        for_each_line(reader, |i, l| {
+            match fresh.as_mut() {
+                None if l.is_empty() => fresh = Some(0),
+                None => parse_range(l, l, &mut ranges).map_err(|e| e.on_line(i))?,
+                Some(count) => {
+                    let id = parse_ingredient(l, l).map_err(|e| e.on_line(i))?;
+                    if Self::contains(&ranges, id) {
+                        *count += 1;
+                    }
+                }
+            }
+            Ok::<_, anyhow::Error>(())
+        })?;
 
         Ok(Self {
+            input: "",
             ranges,
-            ingredients,
+            ingredients: "",
+            streamed: Some(fresh.ok_or_else(|| ParseError::new(MISSING_BLANK_LINE))?),
         })
     }
-}
 
-impl Cafeteria {
-    fn binary_search(&self, i: isize) -> bool {
+    fn contains(ranges: &Intervals, i: isize) -> bool {
         // intervals are already disjoint and sorted, so we can bin search
-        self.ranges
+        ranges
             .intervals
             .binary_search_by(|x| {
                 if x.start > i {
@@ -56,48 +103,137 @@ impl Cafeteria {
     }
 }
 
-impl Problem for Cafeteria {
+impl<'a> BorrowedProblem<'a> for Cafeteria<'a> {
+    const YEAR: usize = 2025;
     const DAY: usize = 5;
     const TITLE: &'static str = "cafeteria";
     const README: &'static str = include_str!("../README.md");
 
+    type At<'b> = Cafeteria<'b>;
+    type Config = ();
     type ProblemError = anyhow::Error;
     type P1 = usize;
     type P2 = usize;
 
-    fn part_one(&mut self) -> Result<Self::P1, Self::ProblemError> {
-        let ret = self
-            .ingredients
-            .iter()
-            .filter(|&i| self.binary_search(*i))
-            .count();
+    fn examples() -> Vec<Example> {
+        vec![Example::file("example.txt").part_one(3).part_two(14)]
+    }
+
+    fn parse_with(raw_input: &'a str, _config: Self::Config) -> Result<Self, Self::ProblemError> {
+        Ok(Self::parse(raw_input)?)
+    }
+
+    fn from_reader(
+        reader: impl BufRead,
+        _config: Self::Config,
+    ) -> Option<Result<Self, Self::ProblemError>> {
+        Some(Self::from_lines(reader))
+    }
+
+    fn part_one(&self) -> Result<Self::P1, Self::ProblemError> {
+        if let Some(fresh) = self.streamed {
+            return Ok(fresh);
+        }
+
+        let mut ret = 0;
+        for x in self.ingredients.lines() {
+            if Self::contains(&self.ranges, parse_ingredient(self.input, x)?) {
+                ret += 1;
+            }
+        }
 
         Ok(ret)
     }
 
-    fn part_two(&mut self) -> Result<Self::P2, Self::ProblemError> {
+    fn part_two(&self) -> Result<Self::P2, Self::ProblemError> {
         Ok(self.ranges.len())
     }
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CafeteriaSize {
+    /// The number of fresh ranges, which may overlap.
+    pub ranges: usize,
+    /// The number of available ingredients.
+    pub ingredients: usize,
+    /// The largest ingredient id.
+    pub max_id: u64,
+    /// The most ids a range can span.
+    pub max_width: u64,
+}
+
+impl Default for CafeteriaSize {
+    fn default() -> Self {
+        Self {
+            ranges: 200,
+            ingredients: 1000,
+            max_id: 560_000_000_000_000,
+            max_width: 10_000_000_000_000,
+        }
+    }
+}
+
+impl Generate for Cafeteria<'_> {
+    type Size = CafeteriaSize;
+
+    fn generate(rng: &mut impl Rng, size: &Self::Size) -> String {
+        let ranges: Vec<(u64, u64)> = (0..size.ranges)
+            .map(|_| {
+                let start = rng.random_range(1..=size.max_id);
+                let end = start.saturating_add(rng.random_range(0..=size.max_width));
+                (start, end.min(size.max_id))
+            })
+            .collect();
+
+        // pick about half of the ingredients from the ranges, otherwise
+        // almost none of them would be fresh
+        let ingredients = (0..size.ingredients).map(|_| match ranges.choose(rng) {
+            Some(&(start, end)) if rng.random_bool(0.5) => rng.random_range(start..=end),
+            _ => rng.random_range(1..=size.max_id),
+        });
+
+        let mut ret = ranges
+            .iter()
+            .map(|(start, end)| format!("{}-{}", start, end))
+            .collect::<Vec<_>>();
+        ret.push(String::default());
+        ret.extend(ingredients.map(|x| x.to_string()));
+        ret.join("\n")
+    }
+}
+
+impl ImmutableParts for Cafeteria<'_> {}
+
+aoc_plumbing::register_day!(Cafeteria<'_>, parallel);
+aoc_plumbing::register_generator!(Cafeteria<'_>);
+aoc_plumbing::example_tests!(Cafeteria<'_>, full_dataset = (640, 365804144481581_u64));
+
 #[cfg(test)]
 mod tests {
-    use aoc_plumbing::Solution;
+    use aoc_plumbing::{Answer, Solution};
 
     use super::*;
 
     #[test]
-    #[ignore]
-    fn full_dataset() {
-        let input = std::fs::read_to_string("input.txt").expect("Unable to load input");
-        let solution = Cafeteria::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(640, 365804144481581));
+    fn streamed() {
+        let input = std::fs::File::open("example.txt").expect("Unable to load input");
+        let report = Cafeteria::solve_reader(std::io::BufReader::new(input), ());
+        assert_eq!(report.unwrap().unwrap().solution, Solution::new(3, 14));
     }
 
     #[test]
-    fn example() {
+    fn normalized() {
         let input = std::fs::read_to_string("example.txt").expect("Unable to load input");
-        let solution = Cafeteria::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(3, 14));
+        let input = format!("\u{feff}{}\r\n\r\n", input.replace('\n', "\r\n"));
+        let problem = aoc_plumbing::Registry::global().get(2025, 5).unwrap();
+        assert_eq!(
+            problem.solve(&input).unwrap(),
+            Solution::new(Answer::from(3), Answer::from(14))
+        );
+        assert_eq!(
+            problem.instance(&input).unwrap().part_two().unwrap(),
+            Answer::from(14)
+        );
+        assert_eq!(Cafeteria::solve(&input).unwrap(), Solution::new(3, 14));
     }
 }
@@ -1,12 +1,43 @@
 use std::str::FromStr;
 
 use anyhow::anyhow;
-use aoc_plumbing::Problem;
+use aoc_plumbing::{Example, Normalize, Problem, generate::Generate};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operation {
+    Add,
+    Multiply,
+}
+
+impl Operation {
+    fn apply(&self, operands: impl Iterator<Item = u64>) -> u64 {
+        match self {
+            Self::Add => operands.sum(),
+            Self::Multiply => operands.product(),
+        }
+    }
+}
+
+/// The operation of a problem, along with where it sits on the worksheet.
+#[derive(Debug, Clone, Copy)]
+struct Operator {
+    operation: Operation,
+    /// The index of the problem, counting from the left.
+    problem: usize,
+    /// The column of the worksheet that the operator is in.
+    col: usize,
+}
 
 #[derive(Debug, Clone)]
 pub struct TrashCompactor {
-    human_solution: u64,
-    cephalopod_solution: u64,
+    /// The numbers of each problem, read left to right as humans do.
+    human_problems: Vec<Vec<u64>>,
+    /// The number in each column of the worksheet, read top to bottom as
+    /// cephalopods do. Columns without digits are 0.
+    cephalopod_columns: Vec<u64>,
+    operators: Vec<Operator>,
 }
 
 impl FromStr for TrashCompactor {
@@ -14,9 +45,8 @@ impl FromStr for TrashCompactor {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut human_problems = Vec::default();
-        let mut cephalopod_problems = Vec::default();
-        let mut human_solution = 0_u64;
-        let mut cephalopod_solution = 0_u64;
+        let mut cephalopod_columns = Vec::default();
+        let mut operators = Vec::default();
 
         for (i, line) in s.lines().enumerate() {
             let mut jj = 0;
@@ -24,106 +54,186 @@ impl FromStr for TrashCompactor {
             for (col, c) in line.chars().enumerate() {
                 if c.is_whitespace() {
                     whitespace = true;
-                } else if c.is_ascii_digit() {
-                    if whitespace {
-                        jj += 1;
-                        whitespace = false;
-                    }
-
-                    let digit = c.to_digit(10).ok_or_else(|| anyhow!("Invalid digit"))? as u64;
-                    let j = jj - 1;
-
-                    if human_problems.len() <= j {
-                        human_problems.push(Vec::default());
-                    }
-                    if human_problems[j].len() <= i {
-                        human_problems[j].push(0);
-                    }
-                    human_problems[j][i] = human_problems[j][i] * 10 + digit;
-
-                    while cephalopod_problems.len() <= col {
-                        cephalopod_problems.push(0);
-                    }
-                    cephalopod_problems[col] = cephalopod_problems[col] * 10 + digit;
-                } else if c == '+' {
-                    if whitespace {
-                        jj += 1;
-                        whitespace = false;
-                    }
-
-                    let j = jj - 1;
-                    human_solution += human_problems[j].iter().sum::<u64>();
-
-                    let mut sum = 0;
-                    let mut cc = col;
-                    while cc < cephalopod_problems.len() && cephalopod_problems[cc] != 0 {
-                        sum += cephalopod_problems[cc];
-                        cc += 1;
-                    }
-                    cephalopod_solution += sum;
-                } else if c == '*' {
-                    if whitespace {
-                        jj += 1;
-                        whitespace = false;
-                    }
-
-                    let j = jj - 1;
-                    human_solution += human_problems[j].iter().product::<u64>();
-
-                    let mut product = 1;
-                    let mut cc = col;
-                    while cc < cephalopod_problems.len() && cephalopod_problems[cc] != 0 {
-                        product *= cephalopod_problems[cc];
-                        cc += 1;
-                    }
-                    cephalopod_solution += product;
+                    continue;
+                }
+
+                let operation = match c {
+                    '+' => Some(Operation::Add),
+                    '*' => Some(Operation::Multiply),
+                    _ if c.is_ascii_digit() => None,
+                    _ => continue,
+                };
+
+                if whitespace {
+                    jj += 1;
+                    whitespace = false;
                 }
+                let j = jj - 1;
+
+                if let Some(operation) = operation {
+                    operators.push(Operator {
+                        operation,
+                        problem: j,
+                        col,
+                    });
+                    continue;
+                }
+
+                let digit = c.to_digit(10).ok_or_else(|| anyhow!("Invalid digit"))? as u64;
+
+                if human_problems.len() <= j {
+                    human_problems.push(Vec::default());
+                }
+                if human_problems[j].len() <= i {
+                    human_problems[j].push(0);
+                }
+                human_problems[j][i] = human_problems[j][i] * 10 + digit;
+
+                while cephalopod_columns.len() <= col {
+                    cephalopod_columns.push(0);
+                }
+                cephalopod_columns[col] = cephalopod_columns[col] * 10 + digit;
             }
         }
 
+        if let Some(op) = operators
+            .iter()
+            .find(|op| op.problem >= human_problems.len())
+        {
+            return Err(anyhow!("No numbers for the problem in column {}", op.col));
+        }
+
         Ok(Self {
-            human_solution,
-            cephalopod_solution,
+            human_problems,
+            cephalopod_columns,
+            operators,
         })
     }
 }
 
+impl TrashCompactor {
+    fn human_solution(&self) -> u64 {
+        self.operators
+            .iter()
+            .map(|op| {
+                op.operation
+                    .apply(self.human_problems[op.problem].iter().copied())
+            })
+            .sum()
+    }
+
+    /// The numbers of each problem are in the columns from its operator up
+    /// to the next column without digits.
+    fn cephalopod_solution(&self) -> u64 {
+        self.operators
+            .iter()
+            .map(|op| {
+                let columns = self.cephalopod_columns.get(op.col..).unwrap_or_default();
+                op.operation
+                    .apply(columns.iter().copied().take_while(|&x| x != 0))
+            })
+            .sum()
+    }
+}
+
 impl Problem for TrashCompactor {
+    const YEAR: usize = 2025;
     const DAY: usize = 6;
     const TITLE: &'static str = "trash compactor";
     const README: &'static str = include_str!("../README.md");
+    // the worksheet is aligned in columns, which trailing whitespace is part of
+    const NORMALIZE: Normalize = Normalize {
+        trailing_whitespace: false,
+        ..Normalize::ALL
+    };
 
+    type Config = ();
     type ProblemError = anyhow::Error;
     type P1 = u64;
     type P2 = u64;
 
-    fn part_one(&mut self) -> Result<Self::P1, Self::ProblemError> {
-        Ok(self.human_solution)
+    fn examples() -> Vec<Example> {
+        vec![
+            Example::file("example.txt")
+                .part_one(4277556)
+                .part_two(3263827),
+        ]
     }
 
-    fn part_two(&mut self) -> Result<Self::P2, Self::ProblemError> {
-        Ok(self.cephalopod_solution)
+    fn part_one(&self) -> Result<Self::P1, Self::ProblemError> {
+        Ok(self.human_solution())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use aoc_plumbing::Solution;
+    fn part_two(&self) -> Result<Self::P2, Self::ProblemError> {
+        Ok(self.cephalopod_solution())
+    }
+}
 
-    use super::*;
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TrashCompactorSize {
+    /// The number of problems on the worksheet.
+    pub problems: usize,
+    /// The number of numbers in each problem.
+    pub operands: usize,
+    /// The most digits a number can have.
+    pub max_digits: usize,
+}
 
-    #[test]
-    #[ignore]
-    fn full_dataset() {
-        let input = std::fs::read_to_string("input.txt").expect("Unable to load input");
-        let solution = TrashCompactor::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(4405895212738, 7450962489289));
+impl Default for TrashCompactorSize {
+    fn default() -> Self {
+        Self {
+            problems: 1000,
+            operands: 4,
+            max_digits: 4,
+        }
     }
+}
+
+impl Generate for TrashCompactor {
+    type Size = TrashCompactorSize;
+
+    fn generate(rng: &mut impl Rng, size: &Self::Size) -> String {
+        let mut rows = vec![String::default(); size.operands + 1];
+
+        for problem in 0..size.problems {
+            let width = rng.random_range(1..=size.max_digits.max(1));
+            let left = rng.random_bool(0.5);
+            // like the real input, each problem is as wide as its longest
+            // number, and all its numbers are aligned to the same side
+            let longest = rng.random_range(0..size.operands.max(1));
+
+            for (i, row) in rows.iter_mut().take(size.operands).enumerate() {
+                let digits = match i == longest {
+                    true => width,
+                    false => rng.random_range(1..=width),
+                };
+                let number: String = (0..digits)
+                    .map(|_| char::from(b'0' + rng.random_range(1..=9)))
+                    .collect();
+
+                if problem > 0 {
+                    row.push(' ');
+                }
+                match left {
+                    true => row.push_str(&format!("{:<width$}", number)),
+                    false => row.push_str(&format!("{:>width$}", number)),
+                }
+            }
 
-    #[test]
-    fn example() {
-        let input = std::fs::read_to_string("example.txt").expect("Unable to load input");
-        let solution = TrashCompactor::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(4277556, 3263827));
+            let operator = if rng.random_bool(0.5) { '+' } else { '*' };
+            if problem > 0 {
+                rows[size.operands].push(' ');
+            }
+            rows[size.operands].push_str(&format!("{:<width$}", operator));
+        }
+
+        rows.join("\n")
     }
 }
+
+aoc_plumbing::register_day!(TrashCompactor);
+aoc_plumbing::register_generator!(TrashCompactor);
+aoc_plumbing::example_tests!(
+    TrashCompactor,
+    full_dataset = (4405895212738_u64, 7450962489289_u64)
+);
@@ -2,7 +2,15 @@ use std::str::FromStr;
 
 use anyhow::{Result, anyhow};
 use aoc_common::grid::Coordinate;
-use aoc_plumbing::Problem;
+use aoc_plumbing::{
+    Answer, Example, Problem,
+    generate::Generate,
+    reference::Reference,
+    visualize::{Color, Scene, Visualize},
+};
+use num::BigUint;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, Default)]
 struct BitSet {
@@ -36,6 +44,14 @@ impl BitSet {
         }
     }
 
+    fn get(&self, i: usize) -> bool {
+        if i < 128 {
+            self.bitset.0 >> (127 - i) & 1 == 1
+        } else {
+            self.bitset.1 >> (255 - i) & 1 == 1
+        }
+    }
+
     fn _unset(&mut self, i: usize) {
         if i < 128 {
             let mask = 1 << (127 - i);
@@ -127,6 +143,7 @@ impl BitSet {
 pub struct Laboratories {
     start: Coordinate,
     splitters: Vec<BitSet>,
+    width: usize,
 }
 
 impl FromStr for Laboratories {
@@ -135,8 +152,10 @@ impl FromStr for Laboratories {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut start = None;
         let mut splitters = Vec::default();
+        let mut width = 0;
 
         for (i, l) in s.lines().enumerate() {
+            width = width.max(l.len());
             for (j, c) in l.chars().enumerate() {
                 if c == 'S' {
                     start = Some(Coordinate::from((i, j)));
@@ -151,6 +170,7 @@ impl FromStr for Laboratories {
         Ok(Self {
             start: start.ok_or_else(|| anyhow!("Could not find start"))?,
             splitters,
+            width,
         })
     }
 }
@@ -161,34 +181,38 @@ impl Laboratories {
         let mut cur = BitSet::default();
         cur.set(self.start.col() as usize);
 
-        for splitter_array in &self.splitters {
+        for (level, splitter_array) in self.splitters.iter().enumerate() {
             let overlap = cur.bitand(splitter_array);
             let splitted = overlap.bitshift_left(1).bitor(&overlap.bitshift_right(1));
             cur = cur.bitxor(&overlap).bitor(&splitted);
             ret += overlap.count_ones();
+            aoc_plumbing::trace!("splits", level = level, count = overlap.count_ones());
         }
 
         ret
     }
 
-    fn simulate_quantum(&self) -> usize {
-        let mut acc = vec![0; 256];
-        acc[self.start.col() as usize] += 1;
+    /// The number of timelines doubles with every splitter, so this can
+    /// easily exceed 64 bits.
+    fn simulate_quantum(&self) -> BigUint {
+        let mut acc = vec![BigUint::default(); 256];
+        acc[self.start.col() as usize] += 1_u8;
 
-        for mut splitter_array in self.splitters.iter().copied() {
+        for (level, mut splitter_array) in self.splitters.iter().copied().enumerate() {
             let mut j = 0;
             while !splitter_array.is_zero() {
                 let offset = splitter_array.leading_zeros();
                 j += offset;
                 splitter_array = splitter_array.bitshift_left(offset + 1);
 
+                let cur = std::mem::take(&mut acc[j]);
+                aoc_plumbing::trace!("split", level = level, col = j, timelines = cur.clone());
                 if j > 0 {
-                    acc[j - 1] += acc[j];
+                    acc[j - 1] += &cur;
                 }
                 if j < acc.len() - 1 {
-                    acc[j + 1] += acc[j];
+                    acc[j + 1] += &cur;
                 }
-                acc[j] = 0;
 
                 j += 1;
             }
@@ -199,41 +223,166 @@ impl Laboratories {
 }
 
 impl Problem for Laboratories {
+    const YEAR: usize = 2025;
     const DAY: usize = 7;
     const TITLE: &'static str = "laboratories";
     const README: &'static str = include_str!("../README.md");
 
+    type Config = ();
     type ProblemError = anyhow::Error;
     type P1 = usize;
-    type P2 = usize;
+    type P2 = Answer;
 
-    fn part_one(&mut self) -> Result<Self::P1, Self::ProblemError> {
+    fn examples() -> Vec<Example> {
+        vec![Example::file("example.txt").part_one(21).part_two(40)]
+    }
+
+    fn part_one(&self) -> Result<Self::P1, Self::ProblemError> {
         Ok(self.simulate())
     }
 
-    fn part_two(&mut self) -> Result<Self::P2, Self::ProblemError> {
-        Ok(self.simulate_quantum())
+    fn part_two(&self) -> Result<Self::P2, Self::ProblemError> {
+        Ok(self.simulate_quantum().into())
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use aoc_plumbing::Solution;
+/// Draws the beams coming down from the start, with the splitters they hit
+/// highlighted. Rows without splitters are dropped when parsing, so every
+/// level is drawn as a row of splitters below a row of beams.
+impl Visualize for Laboratories {
+    fn visualize(&self) -> Scene {
+        let mut scene = Scene::new(self.width as f64, (self.splitters.len() * 2 + 1) as f64);
+        let mut cur = BitSet::default();
+        cur.set(self.start.col() as usize);
+        scene.cell(self.start.col() as usize, 0, Color::GREEN);
+
+        for (level, splitter_array) in self.splitters.iter().enumerate() {
+            for j in 0..self.width {
+                match (cur.get(j), splitter_array.get(j)) {
+                    (true, true) => {
+                        scene.cell(j, level * 2 + 1, Color::CYAN);
+                        scene.cell(j, level * 2 + 2, Color::YELLOW);
+                    }
+                    (true, false) => {
+                        scene.cell(j, level * 2 + 1, Color::CYAN);
+                        scene.cell(j, level * 2 + 2, Color::CYAN);
+                    }
+                    (false, true) => {
+                        scene.cell(j, level * 2 + 2, Color::GRAY);
+                    }
+                    (false, false) => {}
+                }
+            }
 
-    use super::*;
+            let overlap = cur.bitand(splitter_array);
+            let splitted = overlap.bitshift_left(1).bitor(&overlap.bitshift_right(1));
+            cur = cur.bitxor(&overlap).bitor(&splitted);
+        }
 
-    #[test]
-    #[ignore]
-    fn full_dataset() {
-        let input = std::fs::read_to_string("input.txt").expect("Unable to load input");
-        let solution = Laboratories::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(1516, 1393669447690));
+        scene
     }
+}
 
-    #[test]
-    fn example() {
-        let input = std::fs::read_to_string("example.txt").expect("Unable to load input");
-        let solution = Laboratories::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(21, 40));
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LaboratoriesSize {
+    /// The number of rows of splitters. The grid is twice as wide as this,
+    /// and twice as tall, give or take.
+    pub levels: usize,
+    /// The chance of each reachable position having a splitter.
+    pub density: f64,
+}
+
+impl Default for LaboratoriesSize {
+    fn default() -> Self {
+        Self {
+            levels: 70,
+            density: 0.6,
+        }
     }
 }
+
+impl Generate for Laboratories {
+    type Size = LaboratoriesSize;
+
+    fn generate(rng: &mut impl Rng, size: &Self::Size) -> String {
+        let LaboratoriesSize { levels, density } = *size;
+        let half = levels;
+        let width = half * 2 + 1;
+        let mut rows = vec![".".repeat(width); levels * 2 + 2];
+        rows[0].replace_range(half..half + 1, "S");
+
+        // like the real input, splitters are on every other row, fanning out
+        // from the start on alternating columns, so they are never adjacent
+        // and the beams never leave the grid
+        for k in 1..=levels {
+            rows[k * 2] = (0..width)
+                .map(|i| {
+                    let offset = i.abs_diff(half);
+                    match offset < k && (offset + k - 1) % 2 == 0 && rng.random::<f64>() < density {
+                        true => '^',
+                        false => '.',
+                    }
+                })
+                .collect();
+        }
+
+        rows.join("\n")
+    }
+}
+
+/// Follows the beams through the grid one row at a time.
+impl Reference for Laboratories {
+    fn small() -> Self::Size {
+        LaboratoriesSize {
+            levels: 8,
+            ..Default::default()
+        }
+    }
+
+    fn reference_part_one(input: &str) -> Result<Answer> {
+        Ok(reference_beams(input)?.0.into())
+    }
+
+    fn reference_part_two(input: &str) -> Result<Answer> {
+        Ok(reference_beams(input)?.1.into())
+    }
+}
+
+/// Returns the number of splits and the number of timelines.
+fn reference_beams(input: &str) -> Result<(usize, BigUint)> {
+    let rows: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+    let start = rows
+        .first()
+        .and_then(|row| row.iter().position(|&c| c == b'S'))
+        .ok_or_else(|| anyhow!("Could not find start"))?;
+
+    let mut splits = 0;
+    let mut timelines = vec![BigUint::default(); rows[0].len()];
+    timelines[start] += 1_u8;
+
+    for row in &rows[1..] {
+        let mut next = vec![BigUint::default(); row.len()];
+        for (i, count) in timelines.iter().enumerate() {
+            if *count == BigUint::default() {
+                continue;
+            }
+
+            if row[i] == b'^' {
+                splits += 1;
+                next[i - 1] += count;
+                next[i + 1] += count;
+            } else {
+                next[i] += count;
+            }
+        }
+        timelines = next;
+    }
+
+    Ok((splits, timelines.iter().sum()))
+}
+
+aoc_plumbing::register_day!(Laboratories);
+aoc_plumbing::register_generator!(Laboratories);
+aoc_plumbing::register_visualizer!(Laboratories);
+aoc_plumbing::example_tests!(Laboratories, full_dataset = (1516, 1393669447690_u64));
+aoc_plumbing::reference_tests!(Laboratories);
@@ -1,21 +1,33 @@
-use std::{cmp, collections::BinaryHeap, str::FromStr};
+use std::str::FromStr;
 
 use aoc_common::algebra::Point3;
-use aoc_plumbing::Problem;
-use rustc_hash::FxHashMap;
-
+use aoc_plumbing::{
+    Example, ParseError, Problem,
+    generate::Generate,
+    validate::Diagnostic,
+    visualize::{Color, Scene, Visualize},
+};
+use rand::Rng;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+
+/// Coordinates are kept below this so that neither the squared distances nor
+/// the product of two x coordinates overflow a `u64`.
+const MAX_COORDINATE: u64 = 1 << 31;
+
+/// A pair of junction boxes, by their index in the input.
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
 struct PointPair {
-    a: Point3<u64>,
-    b: Point3<u64>,
+    a: usize,
+    b: usize,
     dist_sq: u64,
 }
 
 impl PointPair {
-    pub fn new(a: Point3<u64>, b: Point3<u64>) -> Self {
-        let dx = a.x.abs_diff(b.x);
-        let dy = a.y.abs_diff(b.y);
-        let dz = a.z.abs_diff(b.z);
+    pub fn new(points: &[Point3<u64>], a: usize, b: usize) -> Self {
+        let dx = points[a].x.abs_diff(points[b].x);
+        let dy = points[a].y.abs_diff(points[b].y);
+        let dz = points[a].z.abs_diff(points[b].z);
 
         Self {
             a,
@@ -25,168 +37,347 @@ impl PointPair {
     }
 }
 
-impl PartialOrd for PointPair {
-    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        Some(self.cmp(other))
+/// The circuits formed so far, as a disjoint set of junction boxes.
+#[derive(Debug, Clone)]
+struct Circuits {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl Circuits {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+        }
+    }
+
+    fn root(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    /// Joins the circuits of `a` and `b`, returning the size of the joined
+    /// circuit, or `None` if they were already in the same one.
+    fn connect(&mut self, a: usize, b: usize) -> Option<usize> {
+        let (a, b) = (self.root(a), self.root(b));
+        if a == b {
+            return None;
+        }
+
+        // move the smaller circuit to the bigger one
+        let (small, big) = match self.size[a] < self.size[b] {
+            true => (a, b),
+            false => (b, a),
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        Some(self.size[big])
     }
+
+    /// The sizes of every circuit.
+    fn sizes(&self) -> Vec<usize> {
+        (0..self.parent.len())
+            .filter(|&i| self.parent[i] == i)
+            .map(|i| self.size[i])
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PlaygroundConfig {
+    /// The number of closest pairs to connect for part one.
+    pub connections: usize,
 }
 
-impl Ord for PointPair {
-    fn cmp(&self, other: &Self) -> cmp::Ordering {
-        other.dist_sq.cmp(&self.dist_sq)
+impl Default for PlaygroundConfig {
+    fn default() -> Self {
+        Self { connections: 1000 }
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct Playground {
     points: Vec<Point3<u64>>,
-    point_pairs: BinaryHeap<PointPair>,
-    example: usize,
-    part1: usize,
-    part2: u64,
+    /// Every pair of junction boxes, closest first.
+    point_pairs: Vec<PointPair>,
+    config: PlaygroundConfig,
 }
 
 impl FromStr for Playground {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, PlaygroundConfig::default())
+    }
+}
+
+impl Playground {
+    fn parse(s: &str, config: PlaygroundConfig) -> Result<Self, ParseError> {
         let mut points = Vec::default();
-        let mut point_pairs = BinaryHeap::new();
 
         for l in s.lines() {
             let parsed: Vec<_> = l
                 .split(',')
-                .map(|x| x.parse::<u64>())
+                .map(|x| match x.parse::<u64>() {
+                    Ok(v) if v < MAX_COORDINATE => Ok(v),
+                    Ok(_) => Err(ParseError::spanning(
+                        s,
+                        x,
+                        format!("expected a coordinate below {}", MAX_COORDINATE),
+                    )),
+                    Err(e) => Err(ParseError::spanning(
+                        s,
+                        x,
+                        format!("invalid coordinate: {}", e),
+                    )),
+                })
                 .collect::<Result<_, _>>()?;
-            let point = Point3::new(parsed[0], parsed[1], parsed[2]);
 
-            for p in &points {
-                point_pairs.push(PointPair::new(*p, point));
-            }
-
-            points.push(point);
+            let [x, y, z] = parsed[..] else {
+                return Err(ParseError::spanning(
+                    s,
+                    l,
+                    format!("expected 3 coordinates, found {}", parsed.len()),
+                ));
+            };
+            points.push(Point3::new(x, y, z));
         }
 
-        let mut ret = Self {
+        let mut point_pairs: Vec<_> = (0..points.len())
+            .flat_map(|b| (0..b).map(move |a| (a, b)))
+            .map(|(a, b)| PointPair::new(&points, a, b))
+            .collect();
+        point_pairs.sort_by_key(|pair| pair.dist_sq);
+
+        Ok(Self {
             points,
             point_pairs,
-            example: 0,
-            part1: 0,
-            part2: 0,
-        };
-
-        ret.process();
+            config,
+        })
+    }
 
-        Ok(ret)
+    /// Connects `pair`, returning the size of the circuit it joined into, if
+    /// it joined two circuits.
+    fn connect(&self, circuits: &mut Circuits, i: usize, pair: &PointPair) -> Option<usize> {
+        let size = circuits.connect(pair.a, pair.b)?;
+        let (a, b) = (self.points[pair.a], self.points[pair.b]);
+
+        aoc_plumbing::trace!(
+            "merge",
+            connection = i + 1,
+            a = format!("{},{},{}", a.x, a.y, a.z),
+            b = format!("{},{},{}", b.x, b.y, b.z),
+            size = size,
+        );
+
+        Some(size)
     }
-}
 
-impl Playground {
-    fn root(point: &Point3<u64>, adj: &FxHashMap<Point3<u64>, Vec<Point3<u64>>>) -> Point3<u64> {
-        let ns = &adj[point];
-        if ns.len() == 1 {
-            // ok to unwrap, we know len is 1
-            return *ns.iter().next().unwrap();
+    /// The product of the sizes of the three largest circuits, after
+    /// connecting the configured number of closest pairs.
+    fn largest_circuits(&self) -> usize {
+        let mut circuits = Circuits::new(self.points.len());
+
+        for (i, pair) in self
+            .point_pairs
+            .iter()
+            .take(self.config.connections)
+            .enumerate()
+        {
+            self.connect(&mut circuits, i, pair);
         }
-        *point
-    }
-
-    fn process(&mut self) {
-        let mut adj: FxHashMap<Point3<u64>, Vec<Point3<u64>>> =
-            self.points.iter().map(|x| (*x, Vec::default())).collect();
-
-        let mut i = 0;
-        while let Some(pair) = self.point_pairs.pop() {
-            i += 1;
-            let a_root = Self::root(&pair.a, &adj);
-            let b_root = Self::root(&pair.b, &adj);
-
-            if a_root != b_root {
-                // move the smaller group to bigger group
-                let mut small_group = Vec::default();
-                let big_group_root = if adj[&a_root].len() < adj[&b_root].len() {
-                    small_group.extend(&adj[&a_root]);
-                    small_group.push(a_root);
-                    b_root
-                } else {
-                    small_group.extend(&adj[&b_root]);
-                    small_group.push(b_root);
-                    a_root
-                };
-
-                for p in &small_group {
-                    adj.entry(*p).and_modify(|x| {
-                        x.clear();
-                        x.push(big_group_root);
-                    });
-                    adj.entry(big_group_root).and_modify(|x| {
-                        x.push(*p);
-                    });
-                }
-
-                if adj[&big_group_root].len() == self.points.len() - 1 {
-                    self.part2 = pair.a.x * pair.b.x;
-
-                    if self.part1 > 0 {
-                        return;
-                    }
-                }
-            }
 
-            if i == 10 {
-                let mut circuits: Vec<_> = adj.values().map(|v| v.len() + 1).collect();
-                circuits.sort();
-                self.example = circuits.iter().rev().take(3).product();
-            } else if i == 1000 {
-                let mut circuits: Vec<_> = adj.values().map(|v| v.len() + 1).collect();
-                circuits.sort();
-                self.part1 = circuits.iter().rev().take(3).product();
-
-                if self.part2 > 0 {
-                    return;
-                }
+        let mut sizes = circuits.sizes();
+        sizes.sort();
+        sizes.iter().rev().take(3).product()
+    }
+
+    /// The product of the x coordinates of the pair whose connection joins
+    /// every junction box into a single circuit.
+    fn last_connection(&self) -> u64 {
+        let mut circuits = Circuits::new(self.points.len());
+
+        for (i, pair) in self.point_pairs.iter().enumerate() {
+            if self.connect(&mut circuits, i, pair) == Some(self.points.len()) {
+                return self.points[pair.a].x * self.points[pair.b].x;
             }
         }
+
+        0
     }
 }
 
 impl Problem for Playground {
+    const YEAR: usize = 2025;
     const DAY: usize = 8;
     const TITLE: &'static str = "playground";
     const README: &'static str = include_str!("../README.md");
 
+    type Config = PlaygroundConfig;
     type ProblemError = anyhow::Error;
     type P1 = usize;
     type P2 = u64;
 
-    fn part_one(&mut self) -> Result<Self::P1, Self::ProblemError> {
-        Ok(self.part1)
+    fn examples() -> Vec<Example> {
+        vec![
+            Example::file("example.txt")
+                .param("connections", 10)
+                .part_one(40)
+                .part_two(25272),
+        ]
     }
 
-    fn part_two(&mut self) -> Result<Self::P2, Self::ProblemError> {
-        Ok(self.part2)
+    fn instance_with(raw_input: &str, config: Self::Config) -> Result<Self, ParseError> {
+        Self::parse(raw_input, config)
     }
+
+    fn part_one(&self) -> Result<Self::P1, Self::ProblemError> {
+        Ok(self.largest_circuits())
+    }
+
+    fn part_two(&self) -> Result<Self::P2, Self::ProblemError> {
+        Ok(self.last_connection())
+    }
+
+    fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::default();
+
+        if self.points.len() < 2 {
+            diagnostics.push(Diagnostic::error(format!(
+                "expected at least 2 junction boxes to connect, found {}",
+                self.points.len()
+            )));
+        } else if self.config.connections > self.point_pairs.len() {
+            diagnostics.push(Diagnostic::warning(format!(
+                "{} connections were asked for, but there are only {} pairs of junction boxes",
+                self.config.connections,
+                self.point_pairs.len()
+            )));
+        }
+
+        // boxes in the same place are at a distance of 0 from each other, so
+        // the order they are connected in is ambiguous
+        let mut seen = FxHashMap::default();
+        for (i, point) in self.points.iter().enumerate() {
+            if let Some(first) = seen.insert((point.x, point.y, point.z), i) {
+                diagnostics.push(
+                    Diagnostic::warning(format!(
+                        "junction box is in the same place as the one on line {}",
+                        first + 1
+                    ))
+                    .on_line(i + 1),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+/// Draws the junction boxes from above, with the nearer ones, i.e. those
+/// with a larger z, drawn bigger and warmer on top of the farther ones.
+impl Visualize for Playground {
+    fn visualize(&self) -> Scene {
+        let bound = |f: fn(&Point3<u64>) -> u64| self.points.iter().map(f).max().unwrap_or(0) + 1;
+        let (width, height, depth) = (bound(|p| p.x), bound(|p| p.y), bound(|p| p.z));
+        let radius = width.max(height) as f64 / 200.0;
+        let mut scene = Scene::new(width as f64, height as f64);
+
+        let mut points = self.points.clone();
+        points.sort_by_key(|p| p.z);
+
+        for p in points {
+            let t = p.z as f64 / depth as f64;
+            scene.circle(
+                p.x as f64,
+                p.y as f64,
+                radius * (1.0 + t),
+                Color::BLUE.lerp(Color::RED, t),
+            );
+        }
+
+        scene
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PlaygroundSize {
+    /// The number of junction boxes.
+    pub points: usize,
+    /// The bound on each coordinate.
+    pub max_coordinate: u64,
 }
 
+impl Default for PlaygroundSize {
+    fn default() -> Self {
+        Self {
+            points: 1000,
+            max_coordinate: 100000,
+        }
+    }
+}
+
+impl Generate for Playground {
+    type Size = PlaygroundSize;
+
+    fn generate(rng: &mut impl Rng, size: &Self::Size) -> String {
+        let mut seen = FxHashSet::default();
+        let mut ret = Vec::default();
+
+        // the junction boxes are all in different places, so give up
+        // eventually if there isn't room for them all
+        for _ in 0..size.points * 100 {
+            if ret.len() == size.points {
+                break;
+            }
+
+            let point = [(); 3].map(|_| rng.random_range(0..size.max_coordinate.max(1)));
+            if seen.insert(point) {
+                ret.push(format!("{},{},{}", point[0], point[1], point[2]));
+            }
+        }
+
+        ret.join("\n")
+    }
+}
+
+aoc_plumbing::register_day!(Playground);
+aoc_plumbing::register_generator!(Playground);
+aoc_plumbing::register_visualizer!(Playground);
+aoc_plumbing::example_tests!(Playground, full_dataset = (97384, 9003685096_u64));
+
 #[cfg(test)]
 mod tests {
-    use aoc_plumbing::Solution;
-
     use super::*;
 
     #[test]
-    #[ignore]
-    fn full_dataset() {
-        let input = std::fs::read_to_string("input.txt").expect("Unable to load input");
-        let solution = Playground::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(97384, 9003685096));
+    fn coordinate_count() {
+        let err = Playground::from_str("1,2,3\n4,5").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 1: expected 3 coordinates, found 2"
+        );
     }
 
     #[test]
-    fn example() {
-        let input = std::fs::read_to_string("example.txt").expect("Unable to load input");
-        let problem = Playground::from_str(&input).unwrap();
-        assert_eq!(problem.example, 40);
-        assert_eq!(problem.part2, 25272);
+    fn validate() {
+        let diagnostics: Vec<_> = Playground::from_str("1,2,3\n4,5,6\n1,2,3")
+            .unwrap()
+            .validate()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                "warning: 1000 connections were asked for, but there are only 3 pairs of junction boxes",
+                "warning: line 3: junction box is in the same place as the one on line 1",
+            ]
+        );
     }
 }
@@ -1,55 +1,104 @@
-use anyhow::Ok;
 use good_lp::Solution;
 use good_lp::SolverModel;
 use good_lp::default_solver;
 use std::str::FromStr;
 
-use anyhow::{Result, anyhow, bail};
-use aoc_plumbing::Problem;
+use anyhow::{Result, anyhow};
+use aoc_plumbing::{
+    Example, ImmutableParts, ParseError, Problem, context, generate::Generate, validate::Diagnostic,
+};
 use good_lp::Expression;
 use good_lp::ProblemVariables;
 use good_lp::variable;
 use itertools::Itertools;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Lights, and the joltage counters alongside them, are kept as bits of a
+/// `u16`.
+const MAX_LIGHTS: usize = 16;
 
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
 struct Machine {
     target: u16,
     buttons: Vec<u16>,
-    joltages: [usize; 16],
+    joltages: [usize; MAX_LIGHTS],
+    /// The number of indicator lights.
+    lights: usize,
+    /// The number of joltage counters.
+    counters: usize,
 }
 
 impl FromStr for Machine {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut target = 0;
         let mut buttons = Vec::default();
-        let mut joltages = [0; 16];
+        let mut joltages = [0; MAX_LIGHTS];
+        let (mut lights, mut counters) = (0, 0);
+        let too_many = |token: &str, what: &str| {
+            ParseError::spanning(
+                s,
+                token,
+                format!("expected at most {} {}", MAX_LIGHTS, what),
+            )
+        };
 
         for token in s.split_whitespace() {
-            let bytes = token.as_bytes();
+            let inner = |close: char| {
+                token[1..]
+                    .strip_suffix(close)
+                    .ok_or_else(|| ParseError::spanning(s, token, format!("expected '{}'", close)))
+            };
 
             match token.as_bytes()[0] {
-                b'[' => bytes[1..bytes.len() - 1]
-                    .iter()
-                    .enumerate()
-                    .filter(|&(_, c)| *c == b'#')
-                    .for_each(|(i, _)| target |= 1 << i),
+                b'[' => {
+                    let diagram = inner(']')?;
+                    if diagram.len() > MAX_LIGHTS {
+                        return Err(too_many(diagram, "indicator lights"));
+                    }
+
+                    lights = diagram.len();
+                    diagram
+                        .bytes()
+                        .enumerate()
+                        .filter(|&(_, c)| c == b'#')
+                        .for_each(|(i, _)| target |= 1 << i);
+                }
                 b'(' => {
                     let mut button = 0;
-                    for x in token[1..bytes.len() - 1].split(',') {
-                        let num = x.parse::<usize>()?;
-                        let mask = 1 << num;
-                        button |= mask;
+                    for x in inner(')')?.split(',') {
+                        let num = parse_number(s, x)?;
+                        if num >= MAX_LIGHTS {
+                            return Err(ParseError::spanning(
+                                s,
+                                x,
+                                format!("expected a light below {}", MAX_LIGHTS),
+                            ));
+                        }
+                        button |= 1 << num;
                     }
                     buttons.push(button);
                 }
                 b'{' => {
-                    for (i, x) in token[1..bytes.len() - 1].split(',').enumerate() {
-                        joltages[i] = x.parse::<usize>()?;
+                    let values = inner('}')?;
+                    counters = values.split(',').count();
+                    if counters > MAX_LIGHTS {
+                        return Err(too_many(values, "joltages"));
+                    }
+
+                    for (i, x) in values.split(',').enumerate() {
+                        joltages[i] = parse_number(s, x)?;
                     }
                 }
-                _ => bail!("Could not parse machine"),
+                _ => {
+                    return Err(ParseError::spanning(
+                        s,
+                        token,
+                        "expected indicator lights, a button or joltages",
+                    ));
+                }
             }
         }
 
@@ -57,22 +106,86 @@ impl FromStr for Machine {
             target,
             buttons,
             joltages,
+            lights,
+            counters,
         })
     }
 }
 
+fn parse_number(input: &str, token: &str) -> Result<usize, ParseError> {
+    token
+        .parse()
+        .map_err(|e| ParseError::spanning(input, token, format!("invalid number: {}", e)))
+}
+
 impl Machine {
+    /// Problems with this machine, which is on the given 1-based line.
+    fn validate(&self, line: usize) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::default();
+        let wired = self.buttons.iter().fold(0, |acc, b| acc | b);
+
+        if self.lights != self.counters {
+            diagnostics.push(
+                Diagnostic::warning(format!(
+                    "{} indicator lights, but {} joltages",
+                    self.lights, self.counters
+                ))
+                .on_line(line),
+            );
+        }
+
+        // a machine can be all 16 bits wide, which a `u16` cannot be shifted by
+        let width = self.lights.max(self.counters);
+        for (i, button) in self.buttons.iter().enumerate() {
+            if u32::from(*button) >> width != 0 {
+                diagnostics.push(
+                    Diagnostic::warning(format!(
+                        "button {} is wired to a light past the last one",
+                        i + 1
+                    ))
+                    .on_line(line),
+                );
+            }
+        }
+
+        for light in (0..self.lights).filter(|i| self.target & !wired & (1 << i) != 0) {
+            diagnostics.push(
+                Diagnostic::error(format!(
+                    "light {} must be on, but no button toggles it",
+                    light
+                ))
+                .on_line(line),
+            );
+        }
+
+        for (counter, joltage) in self.joltages.iter().enumerate() {
+            if *joltage > 0 && wired & (1 << counter) == 0 {
+                diagnostics.push(
+                    Diagnostic::error(format!(
+                        "joltage {} must reach {}, but no button increases it",
+                        counter, joltage
+                    ))
+                    .on_line(line),
+                );
+            }
+        }
+
+        diagnostics
+    }
+
     fn configure_indicators(&self) -> Result<usize> {
-        (1..=self.buttons.len())
-            .find(|&k| {
-                self.buttons
-                    .iter()
-                    .combinations(k)
-                    .any(|x| x.iter().fold(0, |acc, &item| acc ^ *item) == self.target)
-            })
-            .ok_or_else(|| {
-                anyhow!("Could not find a combination of buttons to configure indicators")
-            })
+        for k in 1..=self.buttons.len() {
+            for x in self.buttons.iter().combinations(k) {
+                context::checkpoint()?;
+                if x.iter().fold(0, |acc, &item| acc ^ *item) == self.target {
+                    return Ok(k);
+                }
+            }
+        }
+
+        Err(anyhow!(
+            "Could not find a combination of buttons to configure indicators"
+        ))
     }
 
     fn configure_joltages(&self) -> Result<usize> {
@@ -92,6 +205,8 @@ impl Machine {
             model = model.with(constraint_expr.eq(Expression::from_other_affine(target as u32)));
         }
 
+        // the solver itself cannot be interrupted, so check before handing off
+        context::check()?;
         let solution = model.solve()?;
 
         let total = vars
@@ -109,54 +224,194 @@ pub struct Factory {
 }
 
 impl FromStr for Factory {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let machines = s.lines().map(Machine::from_str).collect::<Result<_, _>>()?;
+        let machines = s
+            .lines()
+            .enumerate()
+            .map(|(i, l)| Machine::from_str(l).map_err(|e| e.on_line(i + 1)))
+            .collect::<Result<_, _>>()?;
         Ok(Self { machines })
     }
 }
 
 impl Problem for Factory {
+    const YEAR: usize = 2025;
     const DAY: usize = 10;
     const TITLE: &'static str = "factory";
     const README: &'static str = include_str!("../README.md");
 
+    type Config = ();
     type ProblemError = anyhow::Error;
     type P1 = usize;
     type P2 = usize;
 
-    fn part_one(&mut self) -> Result<Self::P1, Self::ProblemError> {
+    fn examples() -> Vec<Example> {
+        vec![Example::file("example.txt").part_one(7).part_two(33)]
+    }
+
+    fn part_one(&self) -> Result<Self::P1, Self::ProblemError> {
         self.machines
             .iter()
             .try_fold(0, |acc, x| x.configure_indicators().map(|v| acc + v))
     }
 
-    fn part_two(&mut self) -> Result<Self::P2, Self::ProblemError> {
+    fn part_two(&self) -> Result<Self::P2, Self::ProblemError> {
         self.machines
             .iter()
             .try_fold(0, |acc, x| x.configure_joltages().map(|v| acc + v))
     }
+
+    fn validate(&self) -> Vec<Diagnostic> {
+        self.machines
+            .iter()
+            .enumerate()
+            .flat_map(|(i, machine)| machine.validate(i + 1))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FactorySize {
+    /// The number of machines.
+    pub machines: usize,
+    /// The most indicator lights a machine can have, up to 16.
+    pub max_lights: usize,
+    /// The most times each button is pressed to reach the joltages.
+    pub max_presses: usize,
+}
+
+impl Default for FactorySize {
+    fn default() -> Self {
+        Self {
+            machines: 162,
+            max_lights: 10,
+            max_presses: 30,
+        }
+    }
+}
+
+impl Generate for Factory {
+    type Size = FactorySize;
+
+    fn generate(rng: &mut impl Rng, size: &Self::Size) -> String {
+        let max_lights = size.max_lights.clamp(3, 16);
+
+        (0..size.machines)
+            .map(|_| {
+                let lights = rng.random_range(3..=max_lights);
+                // the real input has a few buttons more or less than lights
+                let count = rng.random_range(lights.saturating_sub(2).max(2)..=lights + 3);
+                let mut buttons: Vec<u16> = (0..count)
+                    .map(|_| rng.random_range(1..1 << lights) as u16)
+                    .collect();
+
+                // make sure that every light can be toggled
+                for light in 0..lights {
+                    if buttons.iter().all(|b| b & (1 << light) == 0) {
+                        let i = rng.random_range(0..count);
+                        buttons[i] |= 1 << light;
+                    }
+                }
+
+                // the target and the joltages are both reachable by
+                // construction, from random presses of the buttons
+                let mut target = buttons
+                    .iter()
+                    .filter(|_| rng.random_bool(0.5))
+                    .fold(0, |acc, b| acc ^ b);
+                if target == 0 {
+                    target = buttons[0];
+                }
+
+                let mut joltages = vec![0; lights];
+                for button in &buttons {
+                    let presses = rng.random_range(0..=size.max_presses);
+                    for (i, joltage) in joltages.iter_mut().enumerate() {
+                        if button & (1 << i) > 0 {
+                            *joltage += presses;
+                        }
+                    }
+                }
+
+                let target: String = (0..lights)
+                    .map(|i| match target & (1 << i) > 0 {
+                        true => '#',
+                        false => '.',
+                    })
+                    .collect();
+                let buttons = buttons.iter().map(|b| {
+                    let lights = (0..lights).filter(|i| b & (1 << i) > 0).join(",");
+                    format!("({})", lights)
+                });
+
+                format!(
+                    "[{}] {} {{{}}}",
+                    target,
+                    buttons.format(" "),
+                    joltages.iter().format(",")
+                )
+            })
+            .join("\n")
+    }
 }
 
+impl ImmutableParts for Factory {}
+
+aoc_plumbing::register_day!(Factory, parallel);
+aoc_plumbing::register_generator!(Factory);
+aoc_plumbing::example_tests!(Factory, full_dataset = (449, 17848));
+
 #[cfg(test)]
 mod tests {
-    use aoc_plumbing::Solution;
-
     use super::*;
 
     #[test]
-    #[ignore]
-    fn full_dataset() {
-        let input = std::fs::read_to_string("input.txt").expect("Unable to load input");
-        let solution = Factory::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(449, 17848));
+    fn too_many_lights() {
+        let err = Factory::from_str("[.................] (0) {1}").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 2: expected at most 16 indicator lights"
+        );
+
+        let err = Factory::from_str("[.#] (0,16) {1,2}").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 9: expected a light below 16"
+        );
+    }
+
+    #[test]
+    fn validate() {
+        let input = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}\n[.#] (0) {1,2,3}";
+        let diagnostics: Vec<_> = Factory::from_str(input)
+            .unwrap()
+            .validate()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                "warning: line 2: 2 indicator lights, but 3 joltages",
+                "error: line 2: light 1 must be on, but no button toggles it",
+                "error: line 2: joltage 1 must reach 2, but no button increases it",
+                "error: line 2: joltage 2 must reach 3, but no button increases it",
+            ]
+        );
     }
 
     #[test]
-    fn example() {
-        let input = std::fs::read_to_string("example.txt").expect("Unable to load input");
-        let solution = Factory::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(7, 33));
+    fn validate_widest_machine() {
+        let input = format!(
+            "[{}] ({}) {{{}}}",
+            "#".repeat(MAX_LIGHTS),
+            (0..MAX_LIGHTS).join(","),
+            vec!["1"; MAX_LIGHTS].join(",")
+        );
+
+        assert!(Factory::from_str(&input).unwrap().validate().is_empty());
     }
 }
@@ -1,155 +1,514 @@
 use std::str::FromStr;
 
-use anyhow::anyhow;
-use aoc_plumbing::Problem;
+use anyhow::{Result, anyhow};
+use aoc_plumbing::{
+    Answer, Example, ImmutableParts, ParseError, Problem,
+    generate::Generate,
+    validate::Diagnostic,
+    visualize::{Color, Scene, Visualize},
+};
+use num::BigUint;
+use rand::{Rng, seq::SliceRandom, seq::index};
 use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
-struct Node([u8; 3]);
+/// A three letter device name.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Node([u8; 3]);
 
-impl FromStr for Node {
-    type Err = anyhow::Error;
+impl TryFrom<String> for Node {
+    type Error = String;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(s.as_bytes().try_into().map(Node)?)
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value, &value)
+            .map_err(|_| format!("'{}' is not a three letter device name", value))
+    }
+}
+
+impl From<Node> for String {
+    fn from(value: Node) -> Self {
+        String::from_utf8_lossy(&value.0).into_owned()
+    }
+}
+
+impl Node {
+    /// Parses `token`, which must be a subslice of `input`.
+    fn parse(input: &str, token: &str) -> Result<Self, ParseError> {
+        token
+            .as_bytes()
+            .try_into()
+            .map(Node)
+            .map_err(|_| ParseError::spanning(input, token, "expected a three letter device name"))
     }
 }
 
-#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+/// Path counts grow exponentially with the depth of the graph, so they are
+/// not bounded by any fixed width integer.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
 struct Memo {
-    dac_count: usize,
-    fft_count: usize,
-    both_count: usize,
-    none_count: usize,
+    dac_count: BigUint,
+    fft_count: BigUint,
+    both_count: BigUint,
+    none_count: BigUint,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ReactorConfig {
+    /// Where the paths for part one start.
+    pub start: Node,
+    /// Where the paths for part two start.
+    pub server: Node,
+    /// Where all paths end.
+    pub out: Node,
+    /// The first device that paths for part two must visit.
+    pub dac: Node,
+    /// The second device that paths for part two must visit.
+    pub fft: Node,
+}
+
+impl Default for ReactorConfig {
+    fn default() -> Self {
+        Self {
+            start: Node(*b"you"),
+            server: Node(*b"svr"),
+            out: Node(*b"out"),
+            dac: Node(*b"dac"),
+            fft: Node(*b"fft"),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Reactor {
     adj: FxHashMap<Node, FxHashSet<Node>>,
+    config: ReactorConfig,
 }
 
 impl FromStr for Reactor {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut adj = FxHashMap::default();
 
         for line in s.lines() {
-            let (left, right) = line
-                .split_once(": ")
-                .ok_or_else(|| anyhow!("Invalid input line"))?;
+            let (left, right) = line.split_once(": ").ok_or_else(|| {
+                ParseError::spanning(s, line, "expected a line of the form 'aaa: bbb ccc'")
+            })?;
 
-            let node = Node::from_str(left)?;
+            let node = Node::parse(s, left)?;
             let ns: FxHashSet<Node> = right
                 .split_whitespace()
-                .map(Node::from_str)
+                .map(|x| Node::parse(s, x))
                 .collect::<Result<_, _>>()?;
             adj.insert(node, ns);
         }
 
-        Ok(Self { adj })
+        Ok(Self {
+            adj,
+            config: ReactorConfig::default(),
+        })
     }
 }
 
 impl Reactor {
-    fn dfs(&self, node: &Node, memo: &mut FxHashMap<Node, usize>) -> usize {
-        if let Some(&count) = memo.get(node) {
-            return count;
+    /// The outputs of `node`, which every device but `out` must have.
+    fn outputs(&self, node: &Node) -> Result<&FxHashSet<Node>> {
+        self.adj
+            .get(node)
+            .ok_or_else(|| anyhow!("device '{}' has no outputs listed", String::from(*node)))
+    }
+
+    fn dfs(&self, node: &Node, memo: &mut FxHashMap<Node, BigUint>) -> Result<BigUint> {
+        if let Some(count) = memo.get(node) {
+            return Ok(count.clone());
         }
 
-        if *node == Node(*b"out") {
-            return 1;
+        if *node == self.config.out {
+            return Ok(1_u8.into());
         }
 
-        let ret = self.adj[node].iter().map(|x| self.dfs(x, memo)).sum();
-        memo.insert(*node, ret);
-        ret
+        let mut ret = BigUint::default();
+        for n in self.outputs(node)? {
+            ret += self.dfs(n, memo)?;
+        }
+        aoc_plumbing::trace!("memo", node = String::from(*node), paths = ret.clone());
+        memo.insert(*node, ret.clone());
+        Ok(ret)
     }
 
-    fn dfs_with_constraints(&self, node: &Node, memo: &mut FxHashMap<Node, Memo>) -> Memo {
-        if let Some(&m) = memo.get(node) {
-            return m;
+    fn dfs_with_constraints(&self, node: &Node, memo: &mut FxHashMap<Node, Memo>) -> Result<Memo> {
+        if let Some(m) = memo.get(node) {
+            return Ok(m.clone());
         }
 
-        if *node == Node(*b"out") {
-            return Memo {
-                dac_count: 0,
-                fft_count: 0,
-                both_count: 0,
-                none_count: 1,
-            };
+        if *node == self.config.out {
+            return Ok(Memo {
+                none_count: 1_u8.into(),
+                ..Memo::default()
+            });
         }
 
         let mut ret = Memo::default();
-        for n in &self.adj[node] {
-            let result = self.dfs_with_constraints(n, memo);
-            ret.dac_count += result.dac_count;
-            ret.fft_count += result.fft_count;
-            ret.both_count += result.both_count;
-            ret.none_count += result.none_count;
-
-            match &node.0 {
-                b"dac" => {
-                    ret.dac_count += result.none_count;
-                    ret.both_count += result.fft_count;
-                }
-                b"fft" => {
-                    ret.fft_count += result.none_count;
-                    ret.both_count += result.dac_count;
+        for n in self.outputs(node)? {
+            let result = self.dfs_with_constraints(n, memo)?;
+            ret.dac_count += &result.dac_count;
+            ret.fft_count += &result.fft_count;
+            ret.both_count += &result.both_count;
+            ret.none_count += &result.none_count;
+
+            if *node == self.config.dac {
+                ret.dac_count += &result.none_count;
+                ret.both_count += &result.fft_count;
+            } else if *node == self.config.fft {
+                ret.fft_count += &result.none_count;
+                ret.both_count += &result.dac_count;
+            }
+        }
+
+        aoc_plumbing::trace!(
+            "memo",
+            node = String::from(*node),
+            dac = ret.dac_count.clone(),
+            fft = ret.fft_count.clone(),
+            both = ret.both_count.clone(),
+            neither = ret.none_count.clone(),
+        );
+        memo.insert(*node, ret.clone());
+        Ok(ret)
+    }
+
+    /// Follows every path from `node`, reporting devices without outputs and
+    /// loops, which would make the path counts fail or never finish. `done`
+    /// maps the devices seen so far to whether all paths from them have been
+    /// followed, which is not yet the case for those on the current path.
+    fn walk(
+        &self,
+        node: Node,
+        done: &mut FxHashMap<Node, bool>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        match done.get(&node) {
+            Some(true) => return,
+            Some(false) => {
+                diagnostics.push(Diagnostic::error(format!(
+                    "device '{}' is part of a loop",
+                    String::from(node)
+                )));
+                return;
+            }
+            None if node == self.config.out => {
+                done.insert(node, true);
+                return;
+            }
+            None => {}
+        }
+
+        done.insert(node, false);
+        match self.outputs(&node) {
+            Ok(ns) => {
+                for n in ns {
+                    self.walk(*n, done, diagnostics);
                 }
-                _ => (),
-            };
+            }
+            Err(e) => diagnostics.push(Diagnostic::error(e)),
         }
+        done.insert(node, true);
+    }
 
-        memo.insert(*node, ret);
-        ret
+    /// Whether there is a path from `from` to `to`.
+    fn reaches(&self, from: Node, to: Node) -> bool {
+        let mut seen = FxHashSet::default();
+        let mut stack = vec![from];
+
+        while let Some(node) = stack.pop() {
+            if node == to {
+                return true;
+            }
+
+            if seen.insert(node) {
+                stack.extend(self.adj.get(&node).into_iter().flatten());
+            }
+        }
+
+        false
     }
 }
 
 impl Problem for Reactor {
+    const YEAR: usize = 2025;
     const DAY: usize = 11;
     const TITLE: &'static str = "reactor";
     const README: &'static str = include_str!("../README.md");
 
+    type Config = ReactorConfig;
     type ProblemError = anyhow::Error;
-    type P1 = usize;
-    type P2 = usize;
+    type P1 = Answer;
+    type P2 = Answer;
 
-    fn part_one(&mut self) -> Result<Self::P1, Self::ProblemError> {
-        Ok(self.dfs(&Node(*b"you"), &mut FxHashMap::default()))
+    fn examples() -> Vec<Example> {
+        vec![
+            Example::file("example.txt").part_one(5),
+            Example::file("example2.txt").part_two(2),
+        ]
     }
 
-    fn part_two(&mut self) -> Result<Self::P2, Self::ProblemError> {
-        let result = self.dfs_with_constraints(&Node(*b"svr"), &mut FxHashMap::default());
-        Ok(result.both_count)
+    fn instance_with(raw_input: &str, config: Self::Config) -> Result<Self, ParseError> {
+        let mut inst = Self::from_str(raw_input)?;
+        inst.config = config;
+        Ok(inst)
+    }
+
+    fn part_one(&self) -> Result<Self::P1, Self::ProblemError> {
+        Ok(self
+            .dfs(&self.config.start, &mut FxHashMap::default())?
+            .into())
+    }
+
+    fn part_two(&self) -> Result<Self::P2, Self::ProblemError> {
+        let result = self.dfs_with_constraints(&self.config.server, &mut FxHashMap::default())?;
+        Ok(result.both_count.into())
+    }
+
+    fn validate(&self) -> Vec<Diagnostic> {
+        let config = &self.config;
+        let mut diagnostics = Vec::default();
+        let mut done = FxHashMap::default();
+        self.walk(config.start, &mut done, &mut diagnostics);
+        self.walk(config.server, &mut done, &mut diagnostics);
+
+        // a missing server is already an error
+        let server = self.adj.contains_key(&config.server);
+        for required in [config.dac, config.fft] {
+            if server && !self.reaches(config.server, required) {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "device '{}' cannot be reached from '{}', so part two has no paths",
+                    String::from(required),
+                    String::from(config.server)
+                )));
+            }
+        }
+
+        diagnostics
     }
 }
 
+impl Reactor {
+    /// The length of the longest path from `node` to a device without
+    /// outputs.
+    fn height(&self, node: &Node, memo: &mut FxHashMap<Node, usize>) -> usize {
+        if let Some(&height) = memo.get(node) {
+            return height;
+        }
+
+        // guards against cycles, which a valid input doesn't have
+        memo.insert(*node, 0);
+        let ret = self
+            .adj
+            .get(node)
+            .into_iter()
+            .flatten()
+            .map(|x| self.height(x, memo) + 1)
+            .max()
+            .unwrap_or(0);
+        memo.insert(*node, ret);
+        ret
+    }
+}
+
+/// Draws the devices in columns by their distance from `out`, so that every
+/// connection flows left to right. The devices that the paths of either part
+/// start at, end at or must visit are highlighted.
+impl Visualize for Reactor {
+    fn visualize(&self) -> Scene {
+        let mut heights = FxHashMap::default();
+        let nodes: FxHashSet<Node> = self
+            .adj
+            .iter()
+            .flat_map(|(node, ns)| std::iter::once(node).chain(ns))
+            .copied()
+            .collect();
+        for node in &nodes {
+            self.height(node, &mut heights);
+        }
+
+        let depth = heights.values().max().map_or(0, |&h| h + 1);
+        let mut columns = vec![Vec::default(); depth];
+        for (node, height) in &heights {
+            columns[depth - 1 - height].push(*node);
+        }
+        for column in &mut columns {
+            column.sort_by_key(|node| node.0);
+        }
+
+        // spread out whichever of the columns or rows there are fewer of, so
+        // that the scene is square
+        let rows = columns.iter().map(Vec::len).max().unwrap_or(0);
+        let size = depth.max(rows).max(1) as f64;
+        let (dx, dy) = (size / depth.max(1) as f64, size / rows.max(1) as f64);
+
+        let mut position = FxHashMap::default();
+        for (x, column) in columns.iter().enumerate() {
+            // centred vertically, so that edges don't all fan out from the top
+            let offset = (rows - column.len()) as f64 / 2.0;
+            for (y, node) in column.iter().enumerate() {
+                position.insert(
+                    *node,
+                    ((x as f64 + 0.5) * dx, (offset + y as f64 + 0.5) * dy),
+                );
+            }
+        }
+
+        let mut scene = Scene::new(size, size);
+        for (node, ns) in &self.adj {
+            for n in ns {
+                scene.line(position[node], position[n], Color::GRAY);
+            }
+        }
+
+        let config = &self.config;
+        for (node, &(x, y)) in &position {
+            let color = match *node {
+                n if n == config.start || n == config.server => Color::GREEN,
+                n if n == config.out => Color::RED,
+                n if n == config.dac || n == config.fft => Color::YELLOW,
+                _ => Color::BLUE,
+            };
+            scene.circle(x, y, 0.4, color);
+        }
+
+        scene
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ReactorSize {
+    /// The number of devices with outputs, including the special ones.
+    pub devices: usize,
+    /// The most outputs a device can have.
+    pub max_outputs: usize,
+    /// How far down the graph an output can reach, which keeps the paths
+    /// long like in the real input.
+    pub window: usize,
+}
+
+impl Default for ReactorSize {
+    fn default() -> Self {
+        Self {
+            devices: 560,
+            max_outputs: 5,
+            window: 40,
+        }
+    }
+}
+
+impl Generate for Reactor {
+    type Size = ReactorSize;
+
+    fn generate(rng: &mut impl Rng, size: &Self::Size) -> String {
+        let ReactorConfig {
+            start,
+            server,
+            out,
+            dac,
+            fft,
+        } = ReactorConfig::default();
+
+        let mut names: FxHashSet<Node> = [start, server, out, dac, fft].into_iter().collect();
+        let mut nodes = vec![server];
+        // there are only so many three letter names, so give up eventually
+        for _ in 0..size.devices * 100 {
+            if nodes.len() + 3 >= size.devices {
+                break;
+            }
+
+            let node = Node([(); 3].map(|_| rng.random_range(b'a'..=b'z')));
+            if names.insert(node) {
+                nodes.push(node);
+            }
+        }
+
+        // the graph is a DAG from the server to out, so order the devices
+        // topologically with the other special ones somewhere in between
+        for node in [start, dac, fft] {
+            let i = rng.random_range(1..=nodes.len());
+            nodes.insert(i, node);
+        }
+        nodes.push(out);
+
+        let window = size.window.max(1);
+        let mut outputs: Vec<Vec<usize>> = vec![Vec::default(); nodes.len() - 1];
+        for (i, outputs) in outputs.iter_mut().enumerate() {
+            let reachable = window.min(nodes.len() - i - 1);
+            let count = rng.random_range(1..=size.max_outputs.clamp(1, reachable));
+            outputs.extend(
+                index::sample(rng, reachable, count)
+                    .iter()
+                    .map(|x| i + 1 + x),
+            );
+        }
+
+        // make sure that every device is an output of some other device
+        for j in 1..nodes.len() {
+            if !outputs.iter().any(|x| x.contains(&j)) {
+                let i = rng.random_range(j.saturating_sub(window)..j);
+                outputs[i].push(j);
+            }
+        }
+
+        let mut lines: Vec<String> = outputs
+            .iter()
+            .enumerate()
+            .map(|(i, outputs)| {
+                let outputs: Vec<String> = outputs.iter().map(|&j| nodes[j].into()).collect();
+                format!("{}: {}", String::from(nodes[i]), outputs.join(" "))
+            })
+            .collect();
+        lines.shuffle(rng);
+        lines.join("\n")
+    }
+}
+
+impl ImmutableParts for Reactor {}
+
+aoc_plumbing::register_day!(Reactor, parallel);
+aoc_plumbing::register_generator!(Reactor);
+aoc_plumbing::register_visualizer!(Reactor);
+aoc_plumbing::example_tests!(Reactor, full_dataset = (670, 332052564714990_u64));
+
 #[cfg(test)]
 mod tests {
-    use aoc_plumbing::Solution;
-
     use super::*;
 
-    #[test]
-    #[ignore]
-    fn full_dataset() {
-        let input = std::fs::read_to_string("input.txt").expect("Unable to load input");
-        let solution = Reactor::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(670, 332052564714990));
+    fn diagnostics(input: &str) -> Vec<String> {
+        Reactor::from_str(input)
+            .unwrap()
+            .validate()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
     }
 
     #[test]
-    fn example_part_one() {
-        let input = std::fs::read_to_string("example.txt").expect("Unable to load input");
-        let mut problem = Reactor::from_str(&input).unwrap();
-        assert_eq!(problem.part_one().unwrap(), 5);
+    fn missing_device() {
+        let input = include_str!("../example2.txt");
+        assert_eq!(
+            diagnostics(input),
+            vec!["error: device 'you' has no outputs listed"]
+        );
+
+        let err = Reactor::from_str(input).unwrap().part_one().unwrap_err();
+        assert_eq!(err.to_string(), "device 'you' has no outputs listed");
     }
 
     #[test]
-    fn example_part_two() {
-        let input = std::fs::read_to_string("example2.txt").expect("Unable to load input");
-        let mut problem = Reactor::from_str(&input).unwrap();
-        assert_eq!(problem.part_two().unwrap(), 2);
+    fn loops() {
+        assert_eq!(
+            diagnostics("you: aaa\naaa: bbb out\nbbb: aaa\nsvr: dac\ndac: out"),
+            vec![
+                "error: device 'aaa' is part of a loop",
+                "warning: device 'fft' cannot be reached from 'svr', so part two has no paths",
+            ]
+        );
     }
 }
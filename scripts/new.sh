@@ -1,90 +1,54 @@
 #!/bin/bash
 set -e
 
-# This bit of crazy generates the new crate and adds the relevant imports and
-# macro lines to the cli and benchmarking crates
-
-cargo generate --no-workspace --path ./template --lib --name "$2" -d day="$1"
+# This generates the new crate and links it into the cli and benchmarks via the
+# aoc-solutions crate. The day registers itself with `register_day!`, but
+# linking it takes two entries in aoc-solutions, a dependency in its Cargo.toml
+# and a `pub use` in its src/lib.rs, both of which are added here.
+#
+# Days of the workspace's own year, as given by the YEAR file, live at its
+# root, while those of any other year live in a directory named after that
+# year, which is added to the workspace the first time one of its days is
+# generated. The year is the third argument, then AOC_YEAR, then the
+# workspace's own year.
+
+WORKSPACE_YEAR=$(cat YEAR)
+YEAR="${3:-${AOC_YEAR:-$WORKSPACE_YEAR}}"
+
+cargo generate --no-workspace --path ./template --lib --name "$2" -d day="$1" -d year="$YEAR"
 
 printf -v DAY "%03d" "$1"
 
 EXPECTED="$2"
 DESIRED="day-${DAY}-$2"
 CRATE_NAME=$(cat "$EXPECTED/crate_ref")
-STRUCT_NAME=$(cat "$EXPECTED/name_ref")
 
 rm "$EXPECTED/crate_ref"
-rm "$EXPECTED/name_ref"
-
-echo "Renaming $EXPECTED to $DESIRED"
-mv "$EXPECTED" "$DESIRED"
-
-IMPORT_REPLACEMENT="use ${CRATE_NAME}::${STRUCT_NAME};\\n// import_marker"
 
-# ====== cli
-
-echo "Appending $EXPECTED to cli"
-echo "$EXPECTED = { path = \"../$DESIRED\" }" >> aoc-cli/Cargo.toml
-
-echo "Modifying cli.rs"
-if [[ $OSTYPE == 'darwin'* ]]; then
-    sed -i '' -e "s#// import_marker#$IMPORT_REPLACEMENT#" aoc-cli/src/cli.rs
-else
-    sed -i "s#// import_marker#$IMPORT_REPLACEMENT#" aoc-cli/src/cli.rs
-fi
-
-COMMAND_REPLACEMENT="(${STRUCT_NAME}, $1),\\n    // command_marker"
-if [[ $OSTYPE == 'darwin'* ]]; then
-    sed -i '' -e "s#// command_marker#$COMMAND_REPLACEMENT#" aoc-cli/src/cli.rs
-else
-    sed -i "s#// command_marker#$COMMAND_REPLACEMENT#" aoc-cli/src/cli.rs
-fi
+if [ "$YEAR" != "$WORKSPACE_YEAR" ]; then
+    DESIRED="${YEAR}/${DESIRED}"
+    mkdir -p "$YEAR"
 
-echo "Modifying fast_cli.rs"
-if [[ $OSTYPE == 'darwin'* ]]; then
-    sed -i '' -e "s#// import_marker#$IMPORT_REPLACEMENT#" aoc-cli/src/fast_cli.rs
-else
-    sed -i "s#// import_marker#$IMPORT_REPLACEMENT#" aoc-cli/src/fast_cli.rs
+    if ! grep -q "\"${YEAR}/day-\*\"" Cargo.toml; then
+        echo "Adding ${YEAR} to the workspace"
+        sed -i "s|    \"day-\*\",|    \"day-*\",\n    \"${YEAR}/day-*\",|" Cargo.toml
+    fi
 fi
 
-COMMAND_REPLACEMENT="(${STRUCT_NAME}, $1),\\n    // command_marker"
-if [[ $OSTYPE == 'darwin'* ]]; then
-    sed -i '' -e "s#// command_marker#$COMMAND_REPLACEMENT#" aoc-cli/src/fast_cli.rs
-else
-    sed -i "s#// command_marker#$COMMAND_REPLACEMENT#" aoc-cli/src/fast_cli.rs
-fi
-
-# ====== benchmarks
-
-echo "Appending $EXPECTED to benchmarks"
-echo "$EXPECTED = { path = \"../$DESIRED\" }" >> aoc-benchmarking/Cargo.toml
-
-echo "Modifying bench_main.rs"
-if [[ $OSTYPE == 'darwin'* ]]; then
-    sed -i '' -e "s#// import_marker#$IMPORT_REPLACEMENT#" aoc-benchmarking/benches/bench_main.rs
-else
-    sed -i "s#// import_marker#$IMPORT_REPLACEMENT#" aoc-benchmarking/benches/bench_main.rs
-fi
-
-# yeah, I'm definitely not proud of this insanity
-BENCH_REPLACEMENT=$(cat <<EOF
-(
-        day_${DAY},
-        "../${DESIRED}/input.txt",
-        ${STRUCT_NAME},
-        "Part 1",
-        "Part 2"
-    ),
-    // bench_marker
-EOF
-)
-BENCH_REPLACEMENT="${BENCH_REPLACEMENT//\\/\\\\}"
-BENCH_REPLACEMENT="${BENCH_REPLACEMENT//\//\\/}"
-BENCH_REPLACEMENT="${BENCH_REPLACEMENT//&/\\&}"
-BENCH_REPLACEMENT="${BENCH_REPLACEMENT//$'\n'/\\n}"
+echo "Renaming $EXPECTED to $DESIRED"
+mv "$EXPECTED" "$DESIRED"
 
-if [[ $OSTYPE == 'darwin'* ]]; then
-    sed -i '' -e "s#// bench_marker#$BENCH_REPLACEMENT#" aoc-benchmarking/benches/bench_main.rs
-else
-    sed -i "s#// bench_marker#$BENCH_REPLACEMENT#" aoc-benchmarking/benches/bench_main.rs
-fi
+# The day has to be both a dependency of aoc-solutions and referred to by it,
+# or it is not linked, which the tests of aoc-solutions check.
+echo "Linking $EXPECTED into aoc-solutions"
+cargo add -p aoc-solutions --path "$DESIRED"
+
+# the `pub use`s form a sorted block, which the new one is inserted into
+LC_ALL=C awk -v new="pub use ${CRATE_NAME};" '
+    /^pub use / && !done && new < $0 { print new; done = 1 }
+    /^pub use / { block = 1 }
+    !/^pub use / && block && !done { print new; done = 1 }
+    { print }
+    END { if (!done) print new }
+' aoc-solutions/src/lib.rs > aoc-solutions/src/lib.rs.tmp
+mv aoc-solutions/src/lib.rs.tmp aoc-solutions/src/lib.rs
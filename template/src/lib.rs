@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use aoc_plumbing::Problem;
+use aoc_plumbing::{Example, Problem};
 
 #[derive(Debug, Clone)]
 pub struct {{project-name|upper_camel_case}};
@@ -14,41 +14,28 @@ impl FromStr for {{project-name|upper_camel_case}} {
 }
 
 impl Problem for {{project-name|upper_camel_case}} {
+    const YEAR: usize = {{year}};
     const DAY: usize = {{day}};
     const TITLE: &'static str = "{{project-name|title_case|downcase}}";
     const README: &'static str = include_str!("../README.md");
 
+    type Config = ();
     type ProblemError = anyhow::Error;
     type P1 = i64;
     type P2 = i64;
 
-    fn part_one(&mut self) -> Result<Self::P1, Self::ProblemError> {
-        Ok(0)
+    fn examples() -> Vec<Example> {
+        vec![Example::file("example.txt").part_one(0).part_two(0)]
     }
 
-    fn part_two(&mut self) -> Result<Self::P2, Self::ProblemError> {
+    fn part_one(&self) -> Result<Self::P1, Self::ProblemError> {
         Ok(0)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use aoc_plumbing::Solution;
-
-    use super::*;
-
-    #[test]
-    #[ignore]
-    fn full_dataset() {
-        let input = std::fs::read_to_string("input.txt").expect("Unable to load input");
-        let solution = {{project-name|upper_camel_case}}::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(0, 0));
-    }
 
-    #[test]
-    fn example() {
-        let input = std::fs::read_to_string("example.txt").expect("Unable to load input");
-        let solution = {{project-name|upper_camel_case}}::solve(&input).unwrap();
-        assert_eq!(solution, Solution::new(0, 0));
+    fn part_two(&self) -> Result<Self::P2, Self::ProblemError> {
+        Ok(0)
     }
 }
+
+aoc_plumbing::register_day!({{project-name|upper_camel_case}});
+aoc_plumbing::example_tests!({{project-name|upper_camel_case}}, full_dataset = (0, 0));